@@ -0,0 +1,1069 @@
+//! stores the running aggregate of a single column. Workers keep one ColumnAggregator per column
+//! they own and feed it values as batches of rows arrive.
+
+use crate::distinct::ValueCounter;
+use crate::encapsulators::{self, DateOrder, NumberUnit, SemanticType, ValueKind};
+use crate::error::SummaryError;
+use crate::parsing;
+use crate::quantiles::{self, QuantileEstimator};
+use crate::settings::{Settings, VarianceKind};
+use chrono::{DateTime, Timelike};
+use std::collections::HashMap;
+
+/// number of example values kept for every value kind
+const MAX_EXAMPLES: usize = 5;
+
+/// number of integers a column of only 0 and 1 needs before it is read as flags, so short count
+/// or id columns keep their integer statistics
+const MIN_BINARY_INTEGERS: u64 = 10;
+
+/// number of distinct shape masks counted per column, values with other masks are only counted
+/// as untracked
+const MAX_PATTERNS: usize = 1000;
+
+/// running state of one column: counts and examples of every value kind, distinct values, the
+/// text profile and the aggregates of every other type. The column type is decided in build_summary
+pub struct ColumnAggregator {
+    // number of values of every kind, indexed by ValueKind::get_index
+    type_counts: [u64; ValueKind::ALL.len()],
+    // first few raw values of every kind
+    type_examples: [Vec<String>; ValueKind::ALL.len()],
+
+    // raw values with the number of times they appear, for the distinct count and top values
+    value_counts: ValueCounter,
+
+    // length in characters of raw values, used if the column ends up as text
+    text_min_length: Option<u64>,
+    text_max_length: u64,
+    text_length_sum: u64,
+
+    // empty fields, counted even when they are treated as nulls
+    empty_count: u64,
+    // raw values starting or ending with whitespace, and values with characters outside ASCII
+    leading_whitespace_count: u64,
+    trailing_whitespace_count: u64,
+    non_ascii_count: u64,
+
+    // whether values of every kind are profiled, which starts with the first text value, and the
+    // number of values profiled
+    profiling: bool,
+    profiled_count: u64,
+    // shape masks of profiled values with their count and the first value having them, values
+    // whose mask did not fit are counted apart
+    patterns: HashMap<String, (u64, String)>,
+    untracked_pattern_count: u64,
+    // reused to build the mask of every value without allocating
+    mask_buffer: String,
+
+    // profiled values recognized as every semantic type, indexed by SemanticType::get_index
+    semantic_counts: [u64; SemanticType::ALL.len()],
+
+    // sum of number values, integers and floats alike
+    sum: f64,
+
+    // smallest and largest number value, with the line of the first row holding them
+    number_min: Option<(f64, u64)>,
+    number_max: Option<(f64, u64)>,
+
+    // units written next to number values with the number of values having them
+    unit_counts: HashMap<NumberUnit, u64>,
+
+    // variables for one-pass standard deviation calculation over number values only
+    m: f64,
+    s: f64,
+    number_count: u64,
+
+    // exact sum and range of integer values, used if the column ends up as integer
+    integer_sum: i128,
+    integer_min: Option<i64>,
+    integer_max: Option<i64>,
+
+    // integer values that are 0 or 1, and the ones among them, so 0/1 columns can be flags
+    binary_integer_count: u64,
+    binary_one_count: u64,
+
+    // boolean words like true/false or yes/no
+    true_count: u64,
+    false_count: u64,
+    // text values that are short flag tokens like y/n, flags only if all values are flags
+    short_true_count: u64,
+    short_false_count: u64,
+
+    // missing values, not counted as any kind
+    null_count: u64,
+
+    // counts of number values to calculate the median and percentiles later
+    quantiles: QuantileEstimator,
+
+    // struct storing the earliest and latest date from file
+    date_aggregate: encapsulators::DateColumn,
+
+    // dates with the year last by first number, second number and year, with their count and one
+    // raw value. They are added to date_aggregate once the order of day and month is known
+    unordered_dates: HashMap<(u32, u32, i32), (u64, String)>,
+
+    // struct storing the earliest and latest timestamp, hour counts and offsets from file
+    datetime_aggregate: encapsulators::DateTimeColumn,
+}
+
+impl ColumnAggregator {
+    /// returns a new ColumnAggregator without any values, with exact or sketch based quantiles and
+    /// the distinct value threshold as set in settings
+    pub fn new(settings: &Settings) -> Self {
+        return ColumnAggregator {
+            type_counts: [0; ValueKind::ALL.len()],
+            type_examples: Default::default(),
+            value_counts: ValueCounter::new(settings.distinct_threshold),
+            text_min_length: None,
+            text_max_length: 0,
+            text_length_sum: 0,
+            empty_count: 0,
+            leading_whitespace_count: 0,
+            trailing_whitespace_count: 0,
+            non_ascii_count: 0,
+            profiling: false,
+            profiled_count: 0,
+            patterns: HashMap::new(),
+            untracked_pattern_count: 0,
+            mask_buffer: String::new(),
+            semantic_counts: [0; SemanticType::ALL.len()],
+            sum: 0.0,
+            number_min: None,
+            number_max: None,
+            unit_counts: HashMap::new(),
+            m: 0.0,
+            s: 0.0,
+            number_count: 0,
+            integer_sum: 0,
+            integer_min: None,
+            integer_max: None,
+            binary_integer_count: 0,
+            binary_one_count: 0,
+            true_count: 0,
+            false_count: 0,
+            short_true_count: 0,
+            short_false_count: 0,
+            null_count: 0,
+            quantiles: QuantileEstimator::new(settings.sketch_size),
+            date_aggregate: encapsulators::DateColumn::new(),
+            unordered_dates: HashMap::new(),
+            datetime_aggregate: encapsulators::DateTimeColumn::new(),
+        };
+    }
+
+    /// updates the aggregate with one value of the column, raw is the field as read from the file
+    /// and line is the line number of its row in the source
+    pub fn add(&mut self, raw: &str, value: encapsulators::ColumnType, line: u64) {
+        if raw.is_empty() {
+            self.empty_count += 1;
+        }
+
+        // feed the value to the aggregate of its type and get its kind
+        let kind = match value {
+            // nulls are only counted, they do not take part in type inference and statistics
+            encapsulators::ColumnType::Null => {
+                self.null_count += 1;
+                return;
+            }
+            encapsulators::ColumnType::Integer(integer_value) => {
+                self.add_number(integer_value as f64, line);
+                self.add_integer(integer_value);
+                ValueKind::Integer
+            }
+            encapsulators::ColumnType::Float(number_value) => {
+                self.add_number(number_value, line);
+                ValueKind::Float
+            }
+            encapsulators::ColumnType::Quantity(number_value, unit) => {
+                self.add_number(number_value, line);
+                *self.unit_counts.entry(unit).or_insert(0) += 1;
+                ValueKind::Float
+            }
+            encapsulators::ColumnType::Date(date_value) => {
+                self.add_date(date_value);
+                ValueKind::Date
+            }
+            encapsulators::ColumnType::UnorderedDate(first, second, year) => {
+                let entry = self
+                    .unordered_dates
+                    .entry((first, second, year))
+                    .or_insert_with(|| (0, raw.to_owned()));
+                entry.0 += 1;
+                ValueKind::Date
+            }
+            encapsulators::ColumnType::DateTime(local, offset) => {
+                self.add_datetime(local, offset);
+                ValueKind::DateTime
+            }
+            encapsulators::ColumnType::Boolean(flag) => {
+                if flag {
+                    self.true_count += 1;
+                } else {
+                    self.false_count += 1;
+                }
+                ValueKind::Boolean
+            }
+            encapsulators::ColumnType::Text(_) => {
+                match parsing::parse_short_boolean(raw) {
+                    Some(true) => self.short_true_count += 1,
+                    Some(false) => self.short_false_count += 1,
+                    None => {}
+                }
+                ValueKind::Text
+            }
+        };
+
+        // tally the kind and keep the first few values of it as examples
+        self.type_counts[kind.get_index()] += 1;
+        let examples = &mut self.type_examples[kind.get_index()];
+        if examples.len() < MAX_EXAMPLES {
+            examples.push(raw.to_owned());
+        }
+
+        self.value_counts.add(raw);
+        self.add_text(raw);
+        // columns of only numbers, dates and flags are not worth profiling, once a text value shows
+        // up the values counted so far are profiled and every later value with them
+        if self.profiling {
+            self.add_shape(raw, 1);
+        } else if kind == ValueKind::Text {
+            self.start_profiling();
+        }
+    }
+
+    /// profiles the values counted so far and every value added later. Values counted before are
+    /// taken from the value counts, so only frequent ones are profiled once those were shrunk
+    fn start_profiling(&mut self) {
+        self.profiling = true;
+        for (value, count) in self.value_counts.get_counts() {
+            self.add_shape(&value, count);
+        }
+    }
+
+    /// handles the raw text of every non-null value: updates length min/max/sum and counts values
+    /// with surrounding whitespace or characters outside ASCII
+    fn add_text(&mut self, raw: &str) {
+        let length = if raw.is_ascii() {
+            raw.len() as u64
+        } else {
+            self.non_ascii_count += 1;
+            raw.chars().count() as u64
+        };
+        if self.text_min_length.is_none_or(|min| length < min) {
+            self.text_min_length = Some(length);
+        }
+        self.text_max_length = self.text_max_length.max(length);
+        self.text_length_sum += length;
+
+        if raw.starts_with(char::is_whitespace) {
+            self.leading_whitespace_count += 1;
+        }
+        if raw.ends_with(char::is_whitespace) {
+            self.trailing_whitespace_count += 1;
+        }
+    }
+
+    /// handles count occurrences of a value in a profiled column: counts the shape mask and the
+    /// semantic type
+    fn add_shape(&mut self, raw: &str, count: u64) {
+        self.profiled_count += count;
+        parsing::write_shape_mask(raw, &mut self.mask_buffer);
+        let has_room = self.patterns.len() < MAX_PATTERNS;
+        match self.patterns.get_mut(self.mask_buffer.as_str()) {
+            Some((current, _)) => *current += count,
+            None if has_room => {
+                self.patterns
+                    .insert(self.mask_buffer.clone(), (count, raw.to_owned()));
+            }
+            None => self.untracked_pattern_count += count,
+        }
+
+        if let Some(semantic_type) = parsing::detect_semantic_type(raw) {
+            self.semantic_counts[semantic_type.get_index()] += count;
+        }
+    }
+
+    /// handles int/float values: adds to sum, updates min/max, counts the value for quantiles,
+    /// handles calculating one pass standard deviation
+    fn add_number(&mut self, number_value: f64, line: u64) {
+        self.number_count += 1;
+
+        if self.number_min.is_none_or(|(min, _)| number_value < min) {
+            self.number_min = Some((number_value, line));
+        }
+        if self.number_max.is_none_or(|(max, _)| number_value > max) {
+            self.number_max = Some((number_value, line));
+        }
+
+        self.sum += number_value;
+        self.quantiles.add(number_value);
+
+        // std calculation
+        let old_m = self.m;
+        self.m += (number_value - self.m) / self.number_count as f64;
+        self.s += (number_value - self.m) * (number_value - old_m)
+    }
+
+    /// handles integer values: adds to the exact sum, updates min/max and counts 0/1 values
+    fn add_integer(&mut self, integer_value: i64) {
+        self.integer_sum += integer_value as i128;
+        if self.integer_min.is_none_or(|min| integer_value < min) {
+            self.integer_min = Some(integer_value);
+        }
+        if self.integer_max.is_none_or(|max| integer_value > max) {
+            self.integer_max = Some(integer_value);
+        }
+
+        if integer_value == 0 || integer_value == 1 {
+            self.binary_integer_count += 1;
+            self.binary_one_count += integer_value as u64;
+        }
+    }
+
+    /// handles dates: updates earliest and latest date
+    fn add_date(&mut self, date_value: chrono::NaiveDate) {
+        // swap earliest if new earliest date found
+        match self.date_aggregate.get_earliest() {
+            Some(date) => {
+                if date_value < date {
+                    self.date_aggregate.set_earliest(date_value);
+                }
+            }
+            None => self.date_aggregate.set_earliest(date_value),
+        }
+
+        // swap latest if new latest date found
+        match self.date_aggregate.get_latest() {
+            Some(date) => {
+                if date_value > date {
+                    self.date_aggregate.set_latest(date_value)
+                }
+            }
+            None => self.date_aggregate.set_latest(date_value),
+        }
+    }
+
+    /// handles timestamps: updates earliest/latest, the hour of day counts and the offsets seen.
+    /// Timestamps without an offset are compared as utc
+    fn add_datetime(&mut self, local: chrono::NaiveDateTime, offset: Option<chrono::FixedOffset>) {
+        let timestamp = match offset {
+            Some(offset) => DateTime::from_naive_utc_and_offset(local - offset, offset),
+            None => local.and_utc().fixed_offset(),
+        };
+
+        if self
+            .datetime_aggregate
+            .get_earliest()
+            .is_none_or(|earliest| timestamp < earliest)
+        {
+            self.datetime_aggregate.set_earliest(timestamp);
+        }
+        if self
+            .datetime_aggregate
+            .get_latest()
+            .is_none_or(|latest| timestamp > latest)
+        {
+            self.datetime_aggregate.set_latest(timestamp);
+        }
+
+        let mut hour_counts = self.datetime_aggregate.get_hour_counts();
+        hour_counts[local.hour() as usize] += 1;
+        self.datetime_aggregate.set_hour_counts(hour_counts);
+
+        if let Some(offset) = offset {
+            let mut offsets = self.datetime_aggregate.get_offsets();
+            if !offsets.contains(&offset) {
+                offsets.push(offset);
+                self.datetime_aggregate.set_offsets(offsets);
+            }
+        }
+    }
+
+    /// merges partial state built by another worker from a different part of the same column into
+    /// this aggregate
+    pub fn merge(&mut self, mut other: ColumnAggregator) {
+        // both parts are profiled if one of them is, before their value counts are combined
+        if self.profiling && !other.profiling {
+            other.start_profiling();
+        } else if other.profiling && !self.profiling {
+            self.start_profiling();
+        }
+
+        for kind in ValueKind::ALL {
+            let index = kind.get_index();
+            self.type_counts[index] += other.type_counts[index];
+
+            let free_slots = MAX_EXAMPLES - self.type_examples[index].len();
+            let other_examples = other.type_examples[index].iter().take(free_slots).cloned();
+            self.type_examples[index].extend(other_examples);
+        }
+
+        self.value_counts.merge(other.value_counts);
+        for (unit, count) in other.unit_counts {
+            *self.unit_counts.entry(unit).or_insert(0) += count;
+        }
+
+        self.text_min_length = match (self.text_min_length, other.text_min_length) {
+            (Some(current), Some(min)) => Some(current.min(min)),
+            (current, min) => current.or(min),
+        };
+        self.text_max_length = self.text_max_length.max(other.text_max_length);
+        self.text_length_sum += other.text_length_sum;
+        self.empty_count += other.empty_count;
+        self.leading_whitespace_count += other.leading_whitespace_count;
+        self.trailing_whitespace_count += other.trailing_whitespace_count;
+        self.non_ascii_count += other.non_ascii_count;
+
+        for (pattern, (count, example)) in other.patterns {
+            let has_room = self.patterns.len() < MAX_PATTERNS;
+            match self.patterns.get_mut(&pattern) {
+                Some((current, _)) => *current += count,
+                None if has_room => {
+                    self.patterns.insert(pattern, (count, example));
+                }
+                None => self.untracked_pattern_count += count,
+            }
+        }
+        self.untracked_pattern_count += other.untracked_pattern_count;
+        self.profiled_count += other.profiled_count;
+        for semantic_type in SemanticType::ALL {
+            let index = semantic_type.get_index();
+            self.semantic_counts[index] += other.semantic_counts[index];
+        }
+
+        self.quantiles.merge(other.quantiles);
+
+        // combine the one pass standard deviation variables of both parts
+        let number_count = self.number_count + other.number_count;
+        if number_count > 0 {
+            let delta = other.m - self.m;
+            let self_weight = self.number_count as f64;
+            let other_weight = other.number_count as f64;
+
+            self.m += delta * other_weight / number_count as f64;
+            self.s += other.s + delta * delta * self_weight * other_weight / number_count as f64;
+        }
+        self.number_count = number_count;
+        self.null_count += other.null_count;
+
+        // on equal values the row that comes first in the source wins
+        if let Some((min, line)) = other.number_min {
+            if self
+                .number_min
+                .is_none_or(|(current, current_line)| (min, line) < (current, current_line))
+            {
+                self.number_min = Some((min, line));
+            }
+        }
+        if let Some((max, line)) = other.number_max {
+            if self.number_max.is_none_or(|(current, current_line)| {
+                max > current || (max == current && line < current_line)
+            }) {
+                self.number_max = Some((max, line));
+            }
+        }
+        self.sum += other.sum;
+
+        self.integer_sum += other.integer_sum;
+        self.integer_min = match (self.integer_min, other.integer_min) {
+            (Some(current), Some(min)) => Some(current.min(min)),
+            (current, min) => current.or(min),
+        };
+        self.integer_max = match (self.integer_max, other.integer_max) {
+            (Some(current), Some(max)) => Some(current.max(max)),
+            (current, max) => current.or(max),
+        };
+        self.binary_integer_count += other.binary_integer_count;
+        self.binary_one_count += other.binary_one_count;
+        self.true_count += other.true_count;
+        self.false_count += other.false_count;
+        self.short_true_count += other.short_true_count;
+        self.short_false_count += other.short_false_count;
+
+        if let Some(timestamp) = other.datetime_aggregate.get_earliest() {
+            if self
+                .datetime_aggregate
+                .get_earliest()
+                .is_none_or(|current| timestamp < current)
+            {
+                self.datetime_aggregate.set_earliest(timestamp);
+            }
+        }
+        if let Some(timestamp) = other.datetime_aggregate.get_latest() {
+            if self
+                .datetime_aggregate
+                .get_latest()
+                .is_none_or(|current| timestamp > current)
+            {
+                self.datetime_aggregate.set_latest(timestamp);
+            }
+        }
+        let mut hour_counts = self.datetime_aggregate.get_hour_counts();
+        for (hour_count, other_count) in hour_counts
+            .iter_mut()
+            .zip(other.datetime_aggregate.get_hour_counts())
+        {
+            *hour_count += other_count;
+        }
+        self.datetime_aggregate.set_hour_counts(hour_counts);
+        let mut offsets = self.datetime_aggregate.get_offsets();
+        for offset in other.datetime_aggregate.get_offsets() {
+            if !offsets.contains(&offset) {
+                offsets.push(offset);
+            }
+        }
+        self.datetime_aggregate.set_offsets(offsets);
+
+        for (parts, (count, raw)) in other.unordered_dates {
+            self.unordered_dates.entry(parts).or_insert((0, raw)).0 += count;
+        }
+
+        if let Some(date) = other.date_aggregate.get_earliest() {
+            match self.date_aggregate.get_earliest() {
+                Some(current) if current <= date => {}
+                _ => self.date_aggregate.set_earliest(date),
+            }
+        }
+        if let Some(date) = other.date_aggregate.get_latest() {
+            match self.date_aggregate.get_latest() {
+                Some(current) if current >= date => {}
+                _ => self.date_aggregate.set_latest(date),
+            }
+        }
+    }
+
+    /// decides the order of day and month for dates with the year last and adds them to the date
+    /// aggregate. Without a forced order, the order that more unambiguous dates are valid in wins,
+    /// day first on a tie. Dates that are invalid in the chosen order are tallied as text. Returns
+    /// the order, None if there were no such dates, whether the order was assumed on a tie and the
+    /// number of ambiguous dates
+    fn resolve_unordered_dates(
+        &mut self,
+        date_order: Option<DateOrder>,
+    ) -> (Option<DateOrder>, bool, u64) {
+        if self.unordered_dates.is_empty() {
+            return (None, false, 0);
+        }
+
+        let mut day_first_count: u64 = 0;
+        let mut month_first_count: u64 = 0;
+        let mut ambiguous_count: u64 = 0;
+        for ((first, second, year), (count, _)) in self.unordered_dates.iter() {
+            let day_first = DateOrder::DayFirst.build_date(*first, *second, *year);
+            let month_first = DateOrder::MonthFirst.build_date(*first, *second, *year);
+            match (day_first, month_first) {
+                // both orders give the same date when the numbers are equal
+                (Some(_), Some(_)) if first != second => ambiguous_count += count,
+                (Some(_), None) => day_first_count += count,
+                (None, Some(_)) => month_first_count += count,
+                _ => {}
+            }
+        }
+
+        let assumed = date_order.is_none() && month_first_count == day_first_count;
+        let date_order = date_order.unwrap_or(if month_first_count > day_first_count {
+            DateOrder::MonthFirst
+        } else {
+            DateOrder::DayFirst
+        });
+
+        let unordered_dates = std::mem::take(&mut self.unordered_dates);
+        for ((first, second, year), (count, raw)) in unordered_dates {
+            match date_order.build_date(first, second, year) {
+                Some(date) => self.add_date(date),
+                None => {
+                    self.type_counts[ValueKind::Date.get_index()] -= count;
+                    self.type_counts[ValueKind::Text.get_index()] += count;
+                    let examples = &mut self.type_examples[ValueKind::Text.get_index()];
+                    if examples.len() < MAX_EXAMPLES {
+                        examples.push(raw);
+                    }
+                }
+            }
+        }
+
+        return (Some(date_order), assumed, ambiguous_count);
+    }
+
+    /// counts short flag tokens like y/n as booleans when every text value is one and all other
+    /// values are booleans
+    fn resolve_short_flags(&mut self) {
+        let short_count = self.short_true_count + self.short_false_count;
+        let text_index = ValueKind::Text.get_index();
+        let boolean_index = ValueKind::Boolean.get_index();
+        let value_count: u64 = self.type_counts.iter().sum();
+        if short_count == 0 || short_count + self.type_counts[boolean_index] != value_count {
+            return;
+        }
+
+        self.type_counts[text_index] -= short_count;
+        self.type_counts[boolean_index] += short_count;
+        self.true_count += self.short_true_count;
+        self.false_count += self.short_false_count;
+        let examples = std::mem::take(&mut self.type_examples[text_index]);
+        let free_slots = MAX_EXAMPLES - self.type_examples[boolean_index].len();
+        self.type_examples[boolean_index].extend(examples.into_iter().take(free_slots));
+    }
+
+    /// returns the kinds of the column type the values vote for: booleans, numbers, dates,
+    /// timestamps or text. The dominant group wins if its share of the non-null values reaches the
+    /// threshold, otherwise the column is text. Booleans win ties with numbers
+    fn infer_kinds(&self, threshold: f64) -> &'static [ValueKind] {
+        const BOOLEAN_KINDS: &[ValueKind] = &[ValueKind::Boolean];
+        const BINARY_KINDS: &[ValueKind] = &[ValueKind::Boolean, ValueKind::Integer];
+        const INTEGER_KINDS: &[ValueKind] = &[ValueKind::Integer];
+        const NUMBER_KINDS: &[ValueKind] = &[ValueKind::Integer, ValueKind::Float];
+        const DATE_KINDS: &[ValueKind] = &[ValueKind::Date];
+        const DATETIME_KINDS: &[ValueKind] = &[ValueKind::DateTime];
+        const TEXT_KINDS: &[ValueKind] = &[ValueKind::Text];
+
+        let value_count: u64 = self.type_counts.iter().sum();
+        if value_count == 0 {
+            return TEXT_KINDS;
+        }
+
+        // booleans are flag tokens, numbers are integers and floats or integers only if there are
+        // no floats
+        let integer_count = self.type_counts[ValueKind::Integer.get_index()];
+        // 0/1 integers are flags only if both values appear in a column of enough integers
+        let binary_zero_count = self.binary_integer_count - self.binary_one_count;
+        let is_binary = integer_count >= MIN_BINARY_INTEGERS
+            && self.binary_integer_count == integer_count
+            && self.binary_one_count > 0
+            && binary_zero_count > 0;
+        let flag_kinds = if is_binary {
+            BINARY_KINDS
+        } else {
+            BOOLEAN_KINDS
+        };
+        let number_kinds = if self.type_counts[ValueKind::Float.get_index()] == 0 {
+            INTEGER_KINDS
+        } else {
+            NUMBER_KINDS
+        };
+
+        let mut dominant_kinds = TEXT_KINDS;
+        let mut dominant_count = self.count_kinds(TEXT_KINDS);
+        for kinds in [flag_kinds, number_kinds, DATE_KINDS, DATETIME_KINDS] {
+            let count = self.count_kinds(kinds);
+            if count > dominant_count {
+                dominant_kinds = kinds;
+                dominant_count = count;
+            }
+        }
+
+        if dominant_count as f64 / value_count as f64 >= threshold {
+            return dominant_kinds;
+        }
+        return TEXT_KINDS;
+    }
+
+    /// returns the k most frequent shape masks with their counts and first values, most frequent
+    /// first. Masks with equal counts are ordered by mask
+    fn get_top_patterns(&self, k: usize) -> Vec<(String, u64, String)> {
+        let mut patterns: Vec<(&String, &(u64, String))> = self.patterns.iter().collect();
+        patterns.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then_with(|| a.0.cmp(b.0)));
+        return patterns
+            .into_iter()
+            .take(k)
+            .map(|(pattern, (count, example))| (pattern.clone(), *count, example.clone()))
+            .collect();
+    }
+
+    /// returns the semantic type matched by the most profiled values with the share of them
+    /// matching it as a percentage, None if it is matched by less than threshold of the profiled
+    /// values
+    fn infer_semantic_type(&self, threshold: f64) -> Option<(SemanticType, f64)> {
+        if self.profiled_count == 0 {
+            return None;
+        }
+        let mut best: Option<(SemanticType, u64)> = None;
+        for semantic_type in SemanticType::ALL {
+            let count = self.semantic_counts[semantic_type.get_index()];
+            if count > 0 && best.is_none_or(|(_, best_count)| count > best_count) {
+                best = Some((semantic_type, count));
+            }
+        }
+
+        let (semantic_type, count) = best?;
+        let share = count as f64 / self.profiled_count as f64;
+        if share < threshold {
+            return None;
+        }
+        return Some((semantic_type, share * 100.0));
+    }
+
+    /// returns the number of values of the passed kinds
+    fn count_kinds(&self, kinds: &[ValueKind]) -> u64 {
+        return kinds
+            .iter()
+            .map(|kind| self.type_counts[kind.get_index()])
+            .sum();
+    }
+
+    /// consumes the aggregate and returns ColumnSummary value based on the inferred column type. A
+    /// column without rows or with only nulls is summarized as a text column without values
+    pub fn build_summary(
+        mut self,
+        column_name: &str,
+        settings: &Settings,
+    ) -> Result<encapsulators::ColumnSummary, SummaryError> {
+        let value_count: u64 = self.type_counts.iter().sum();
+        let column_settings = settings.get_column_settings(column_name);
+        let (date_order, date_order_assumed, ambiguous_count) =
+            self.resolve_unordered_dates(column_settings.date_order);
+        self.resolve_short_flags();
+
+        let column_kinds = self.infer_kinds(settings.type_threshold);
+
+        let mut profile = encapsulators::ColumnProfile::new();
+        profile.set_row_count(value_count + self.null_count);
+        profile.set_null_count(self.null_count);
+        profile.set_top_values(self.value_counts.get_top_values(settings.top_k));
+
+        // values of other kinds than the column type do not conform, a text column accepts all
+        let mut non_conforming_examples: Vec<String> = vec![];
+        for kind in ValueKind::ALL {
+            profile.set_type_count(kind, self.type_counts[kind.get_index()]);
+
+            if !column_kinds.contains(&kind) && !column_kinds.contains(&ValueKind::Text) {
+                let free_slots = MAX_EXAMPLES - non_conforming_examples.len();
+                let examples = self.type_examples[kind.get_index()].iter().take(free_slots);
+                non_conforming_examples.extend(examples.cloned());
+            }
+        }
+        if !column_kinds.contains(&ValueKind::Text) {
+            profile.set_non_conforming_count(value_count - self.count_kinds(column_kinds));
+            profile.set_non_conforming_examples(non_conforming_examples);
+        }
+
+        if column_kinds.contains(&ValueKind::Text) {
+            // a text column without text values, like one split between numbers and dates, is
+            // profiled now
+            if !self.profiling {
+                self.start_profiling();
+            }
+            let mut text_column_summary = encapsulators::TextColumn::new();
+            text_column_summary.set_category_count(self.value_counts.get_distinct_count());
+            text_column_summary.set_category_count_error(self.value_counts.get_distinct_error());
+            text_column_summary.set_min_length(self.text_min_length.unwrap_or(0));
+            text_column_summary.set_max_length(self.text_max_length);
+            if value_count > 0 {
+                text_column_summary
+                    .set_mean_length(self.text_length_sum as f64 / value_count as f64);
+            }
+            text_column_summary.set_empty_count(self.empty_count);
+            text_column_summary.set_leading_whitespace_count(self.leading_whitespace_count);
+            text_column_summary.set_trailing_whitespace_count(self.trailing_whitespace_count);
+            text_column_summary.set_non_ascii_count(self.non_ascii_count);
+            text_column_summary.set_profiled_count(self.profiled_count);
+            text_column_summary.set_pattern_count(self.patterns.len() as u64);
+            text_column_summary.set_untracked_pattern_count(self.untracked_pattern_count);
+            text_column_summary.set_top_patterns(self.get_top_patterns(settings.top_k));
+            text_column_summary
+                .set_semantic_type(self.infer_semantic_type(settings.type_threshold));
+            text_column_summary.set_profile(profile);
+            text_column_summary.set_categories(self.value_counts.into_values());
+
+            return Ok(encapsulators::ColumnSummary::Text(text_column_summary));
+        } else if column_kinds.contains(&ValueKind::Date) {
+            let mut date_column_summary = self.date_aggregate.build_summary();
+            date_column_summary.set_date_order(date_order);
+            date_column_summary.set_date_order_assumed(date_order_assumed);
+            date_column_summary.set_ambiguous_count(ambiguous_count);
+            date_column_summary.set_profile(profile);
+            return Ok(encapsulators::ColumnSummary::Date(date_column_summary));
+        } else if column_kinds.contains(&ValueKind::DateTime) {
+            let mut datetime_column_summary = self.datetime_aggregate.build_summary();
+            let mut offsets = datetime_column_summary.get_offsets();
+            offsets.sort_by_key(|offset| offset.local_minus_utc());
+            datetime_column_summary.set_offsets(offsets);
+            datetime_column_summary.set_profile(profile);
+            return Ok(encapsulators::ColumnSummary::DateTime(
+                datetime_column_summary,
+            ));
+        } else if column_kinds.contains(&ValueKind::Boolean) {
+            let mut boolean_column_summary = encapsulators::BooleanColumn::new();
+            let mut true_count = self.true_count;
+            let mut false_count = self.false_count;
+            // 0/1 integers count as flags when the column accepts them
+            if column_kinds.contains(&ValueKind::Integer) {
+                true_count += self.binary_one_count;
+                false_count += self.binary_integer_count - self.binary_one_count;
+            }
+            boolean_column_summary.set_true_count(true_count);
+            boolean_column_summary.set_false_count(false_count);
+            boolean_column_summary.set_profile(profile);
+            return Ok(encapsulators::ColumnSummary::Boolean(
+                boolean_column_summary,
+            ));
+        } else if column_kinds.contains(&ValueKind::Float) {
+            let number_column_summary = self.build_number_column(column_name, profile, settings)?;
+            return Ok(encapsulators::ColumnSummary::Number(number_column_summary));
+        } else {
+            let mut integer_column_summary = encapsulators::IntegerColumn::new();
+            integer_column_summary.set_sum(self.integer_sum);
+            integer_column_summary.set_min(self.integer_min.unwrap_or(0));
+            integer_column_summary.set_max(self.integer_max.unwrap_or(0));
+            integer_column_summary.set_statistics(self.build_number_column(
+                column_name,
+                profile,
+                settings,
+            )?);
+            return Ok(encapsulators::ColumnSummary::Integer(
+                integer_column_summary,
+            ));
+        }
+    }
+
+    /// consumes the aggregate and returns the float statistics of its number values, with variance
+    /// of the kind set in settings
+    fn build_number_column(
+        self,
+        column_name: &str,
+        profile: encapsulators::ColumnProfile,
+        settings: &Settings,
+    ) -> Result<encapsulators::NumberColumn, SummaryError> {
+        // calculate summary statistics
+        let mean = self.sum / self.number_count as f64;
+        // the sum of squared differences is divided by n or n - 1, a sample of one value has no
+        // variance
+        let divisor = match settings.variance_kind {
+            VarianceKind::Population => self.number_count,
+            VarianceKind::Sample => self.number_count - 1,
+        };
+        let variance = if divisor > 0 {
+            Some(self.s / divisor as f64)
+        } else {
+            None
+        };
+
+        // the median and quartiles are always needed, followed by the percentiles from settings
+        let mut percentiles = vec![50.0, 25.0, 75.0];
+        percentiles.extend(settings.percentiles.iter());
+        let values = self
+            .quantiles
+            .get_percentiles(&percentiles)
+            .ok_or_else(|| SummaryError::EmptyColumn(column_name.to_owned()))?;
+
+        let mut number_column_summary = encapsulators::NumberColumn::new();
+        number_column_summary.set_count(self.number_count);
+        // the most common unit, ties go to the first sign in alphabetical order
+        let unit = self
+            .unit_counts
+            .iter()
+            .max_by(|a, b| {
+                a.1.cmp(b.1)
+                    .then_with(|| b.0.get_name().cmp(a.0.get_name()))
+            })
+            .map(|(unit, _)| *unit);
+        number_column_summary.set_unit(unit);
+        number_column_summary.set_sum(self.sum);
+        if let Some((min, line)) = self.number_min {
+            number_column_summary.set_min(min, line);
+        }
+        if let Some((max, line)) = self.number_max {
+            number_column_summary.set_max(max, line);
+        }
+        number_column_summary.set_mean(mean);
+        number_column_summary.set_median(values[0]);
+        number_column_summary.set_iqr(values[2] - values[1]);
+
+        // approximate percentiles come with the values at the edges of their rank error
+        if let Some(rank_error) = self.quantiles.get_rank_error() {
+            let bound_percentiles: Vec<f64> = settings
+                .percentiles
+                .iter()
+                .flat_map(|p| [p - rank_error * 100.0, p + rank_error * 100.0])
+                .collect();
+            let bounds = self
+                .quantiles
+                .get_percentiles(&bound_percentiles)
+                .unwrap_or_default();
+            number_column_summary.set_rank_error(Some(rank_error));
+            number_column_summary.set_percentile_bounds(
+                bounds.chunks(2).map(|bound| (bound[0], bound[1])).collect(),
+            );
+        }
+        number_column_summary
+            .set_percentiles(percentiles.into_iter().zip(values).skip(3).collect());
+        number_column_summary.set_variance(variance);
+        number_column_summary.set_std(variance.map(|v| v.sqrt()));
+
+        // equal width bins between min and max, a column with a single value gets one bin
+        if let (Some((min, _)), Some((max, _))) = (self.number_min, self.number_max) {
+            let bins = if max > min {
+                quantiles::HISTOGRAM_BINS
+            } else {
+                1
+            };
+            let width = (max - min) / bins as f64;
+            let counts = self.quantiles.get_histogram(min, max, bins);
+            number_column_summary.set_histogram(
+                counts
+                    .into_iter()
+                    .enumerate()
+                    .map(|(bin, count)| {
+                        let lower = min + width * bin as f64;
+                        let upper = if bin + 1 == bins { max } else { lower + width };
+                        (lower, upper, count)
+                    })
+                    .collect(),
+            );
+        }
+        number_column_summary.set_profile(profile);
+        return Ok(number_column_summary);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// returns the aggregate of a column named column holding the passed values
+    fn aggregate(values: &[&str], settings: &Settings) -> ColumnAggregator {
+        let column_settings = settings.get_column_settings("column");
+        let mut aggregator = ColumnAggregator::new(settings);
+        for (line, value) in values.iter().enumerate() {
+            let converted = parsing::convert_value(value, settings, &column_settings);
+            aggregator.add(value, converted, line as u64 + 2);
+        }
+        return aggregator;
+    }
+
+    /// returns the summary of a date column holding the passed values
+    fn summarize(values: &[&str], settings: &Settings) -> encapsulators::DateColumn {
+        return match aggregate(values, settings)
+            .build_summary("column", settings)
+            .unwrap()
+        {
+            encapsulators::ColumnSummary::Date(date_column) => date_column,
+            other => panic!("summarized as {:?}", other),
+        };
+    }
+
+    /// returns the summary of a text column built from the aggregate
+    fn summarize_text(
+        aggregator: ColumnAggregator,
+        settings: &Settings,
+    ) -> encapsulators::TextColumn {
+        return match aggregator.build_summary("column", settings).unwrap() {
+            encapsulators::ColumnSummary::Text(text_column) => text_column,
+            other => panic!("summarized as {:?}", other),
+        };
+    }
+
+    #[test]
+    fn column_without_rows_is_empty_text() {
+        let settings = Settings::new();
+        let summary = ColumnAggregator::new(&settings)
+            .build_summary("empty", &settings)
+            .unwrap();
+        match summary {
+            encapsulators::ColumnSummary::Text(text_column) => {
+                assert_eq!(text_column.get_category_count(), 0);
+                assert_eq!(text_column.get_profile().get_row_count(), 0);
+            }
+            other => panic!("summarized as {:?}", other),
+        }
+    }
+
+    #[test]
+    fn mixed_postal_codes_are_all_profiled() {
+        let settings = Settings::new();
+        let expected = vec![
+            ("99999".to_owned(), 3, 60.0, "12345".to_owned()),
+            ("AA-99".to_owned(), 2, 40.0, "AB-12".to_owned()),
+        ];
+
+        // numbers before the first text value are profiled once it shows up
+        let values = ["12345", "67890", "AB-12", "54321", "CD-34"];
+        let text_column = summarize_text(aggregate(&values, &settings), &settings);
+        assert_eq!(text_column.get_profiled_count(), 5);
+        assert_eq!(text_column.get_top_patterns(), expected);
+
+        // a part without text values is profiled when it is merged with one that has them
+        let mut numbers = aggregate(&["12345", "67890", "54321"], &settings);
+        numbers.merge(aggregate(&["AB-12", "CD-34"], &settings));
+        let text_column = summarize_text(numbers, &settings);
+        assert_eq!(text_column.get_top_patterns(), expected);
+    }
+
+    #[test]
+    fn short_flags_are_booleans_only_in_flag_columns() {
+        let settings = Settings::new();
+        match aggregate(&["Y", "N", "y", "yes", "NO"], &settings)
+            .build_summary("column", &settings)
+            .unwrap()
+        {
+            encapsulators::ColumnSummary::Boolean(boolean_column) => {
+                assert_eq!(boolean_column.get_true_count(), 3);
+                assert_eq!(boolean_column.get_false_count(), 2);
+            }
+            other => panic!("summarized as {:?}", other),
+        }
+
+        let grades = summarize_text(aggregate(&["A", "B", "F", "C", "F"], &settings), &settings);
+        assert_eq!(grades.get_profile().get_type_count(ValueKind::Boolean), 0);
+        let countries = summarize_text(aggregate(&["NO", "DE", "FR", "NO"], &settings), &settings);
+        assert_eq!(
+            countries.get_semantic_type(),
+            Some((SemanticType::CountryCode, 100.0))
+        );
+    }
+
+    #[test]
+    fn all_ambiguous_dates_assume_day_first() {
+        let date_column = summarize(
+            &["01/02/2024", "03/04/2024", "05/05/2024"],
+            &Settings::new(),
+        );
+        assert_eq!(date_column.get_date_order(), Some(DateOrder::DayFirst));
+        assert!(date_column.is_date_order_assumed());
+        assert_eq!(date_column.get_ambiguous_count(), 2);
+        assert_eq!(
+            date_column.get_earliest(),
+            NaiveDate::from_ymd_opt(2024, 2, 1)
+        );
+    }
+
+    #[test]
+    fn unambiguous_dates_decide_the_order() {
+        let date_column = summarize(&["01/02/2024", "12/25/2024"], &Settings::new());
+        assert_eq!(date_column.get_date_order(), Some(DateOrder::MonthFirst));
+        assert!(!date_column.is_date_order_assumed());
+        assert_eq!(date_column.get_ambiguous_count(), 1);
+        assert_eq!(
+            date_column.get_earliest(),
+            NaiveDate::from_ymd_opt(2024, 1, 2)
+        );
+        assert_eq!(
+            date_column.get_latest(),
+            NaiveDate::from_ymd_opt(2024, 12, 25)
+        );
+    }
+
+    #[test]
+    fn forced_date_order_overrides_the_values() {
+        let mut settings = Settings::new();
+        settings.type_threshold = 0.7;
+        settings
+            .date_orders
+            .set(Some("column".to_owned()), Some(DateOrder::DayFirst));
+        let values = ["01/02/2024", "03/04/2024", "05/06/2024", "12/25/2024"];
+        let date_column = summarize(&values, &settings);
+        assert_eq!(date_column.get_date_order(), Some(DateOrder::DayFirst));
+        assert!(!date_column.is_date_order_assumed());
+        assert_eq!(
+            date_column.get_earliest(),
+            NaiveDate::from_ymd_opt(2024, 2, 1)
+        );
+
+        // dates that do not exist in the forced order are text
+        let profile = date_column.get_profile();
+        assert_eq!(profile.get_type_count(ValueKind::Date), 3);
+        assert_eq!(profile.get_type_count(ValueKind::Text), 1);
+        assert_eq!(profile.get_non_conforming_count(), 1);
+    }
+}
@@ -0,0 +1,318 @@
+//! splits a csv byte stream into chunks that end on a record boundary, so that every chunk can be
+//! parsed by a different thread.
+
+use crate::dialect::Dialect;
+use std::io::{self, Read};
+
+/// number of bytes requested from the underlying reader at once
+const READ_SIZE: usize = 1 << 20;
+
+/// piece of the stream that ends on a record boundary
+pub struct Chunk {
+    // line number of the first line in the chunk, starting at 1
+    pub first_line: u64,
+    pub bytes: Vec<u8>,
+}
+
+/// reads raw bytes and hands them out in chunks. A chunk always ends right after a line break that
+/// is not inside a quoted field or a comment, so quoted values spanning several lines are never cut
+/// in half
+pub struct ChunkReader<R: Read> {
+    reader: R,
+    dialect: Dialect,
+    buffer: Vec<u8>,
+    eof: bool,
+    line: u64,
+}
+
+impl<R: Read> ChunkReader<R> {
+    /// returns a new ChunkReader with an empty buffer reading from the passed reader, record
+    /// boundaries are found using the quoting rules of the dialect
+    pub fn new(reader: R, dialect: &Dialect) -> Self {
+        return ChunkReader {
+            reader,
+            dialect: dialect.clone(),
+            buffer: Vec::new(),
+            eof: false,
+            line: 1,
+        };
+    }
+
+    /// returns the next chunk of at least target_size bytes (unless the stream ends earlier) cut
+    /// at the first record boundary after target_size. Returns None when the stream is exhausted.
+    /// Passing target_size of 1 returns a single record, which is used to read the header
+    pub fn next_chunk(&mut self, target_size: usize) -> io::Result<Option<Chunk>> {
+        let delimiter = self.dialect.get_delimiter();
+        let quote = self.dialect.get_quote();
+        let escape = self.dialect.get_escape();
+        let double_quote = self.dialect.get_double_quote();
+        let comment = self.dialect.get_comment();
+
+        // a chunk always starts at the beginning of a record
+        let mut in_quotes = false;
+        let mut escaped = false;
+        let mut quote_closed = false;
+        let mut in_comment = false;
+        let mut line_start = true;
+        let mut field_start = true;
+
+        let mut position: usize = 0;
+        let mut line_count: u64 = 0;
+
+        loop {
+            // go over the buffered bytes and look for a line break outside of quotes
+            while position < self.buffer.len() {
+                let byte = self.buffer[position];
+                position += 1;
+
+                if byte == b'\n' {
+                    line_count += 1;
+                }
+
+                if in_quotes {
+                    if escaped {
+                        escaped = false;
+                    } else if Some(byte) == escape {
+                        escaped = true;
+                    } else if byte == quote {
+                        in_quotes = false;
+                        quote_closed = true;
+                    }
+                    continue;
+                }
+
+                if quote_closed {
+                    quote_closed = false;
+                    // a doubled quote stands for one quote and the field stays quoted
+                    if byte == quote && double_quote {
+                        in_quotes = true;
+                        continue;
+                    }
+                }
+
+                if byte == b'\n' {
+                    if position >= target_size {
+                        // keep everything after the boundary for the next chunk
+                        let rest = self.buffer.split_off(position);
+                        let bytes = std::mem::replace(&mut self.buffer, rest);
+                        return Ok(Some(self.build_chunk(bytes, line_count)));
+                    }
+
+                    in_comment = false;
+                    line_start = true;
+                    field_start = true;
+                    continue;
+                }
+
+                if in_comment {
+                    continue;
+                }
+                if line_start && Some(byte) == comment {
+                    in_comment = true;
+                    line_start = false;
+                    continue;
+                }
+                line_start = false;
+
+                // quotes only start a quoted field at the beginning of the field
+                if byte == delimiter {
+                    field_start = true;
+                } else if byte == quote && field_start {
+                    in_quotes = true;
+                    field_start = false;
+                } else {
+                    field_start = false;
+                }
+            }
+
+            if self.eof {
+                // the last chunk does not have to end with a line break
+                if self.buffer.is_empty() {
+                    return Ok(None);
+                }
+                let bytes = std::mem::take(&mut self.buffer);
+                return Ok(Some(self.build_chunk(bytes, line_count)));
+            }
+
+            self.fill_buffer()?;
+        }
+    }
+
+    /// returns up to size bytes from the start of the stream without consuming them, fewer bytes
+    /// are returned only when the stream is shorter
+    pub fn peek(&mut self, size: usize) -> io::Result<&[u8]> {
+        while self.buffer.len() < size && !self.eof {
+            self.fill_buffer()?;
+        }
+
+        return Ok(&self.buffer[..size.min(self.buffer.len())]);
+    }
+
+    /// replaces the dialect used to find record boundaries
+    pub fn set_dialect(&mut self, dialect: &Dialect) {
+        self.dialect = dialect.clone();
+    }
+
+    /// wraps the bytes in a Chunk and moves the line counter past the lines it contains
+    fn build_chunk(&mut self, bytes: Vec<u8>, line_count: u64) -> Chunk {
+        let chunk = Chunk {
+            first_line: self.line,
+            bytes,
+        };
+        self.line += line_count;
+
+        return chunk;
+    }
+
+    /// appends up to READ_SIZE bytes from the reader to the buffer, marks the end of stream when
+    /// nothing more can be read
+    fn fill_buffer(&mut self) -> io::Result<()> {
+        let filled = self.buffer.len();
+        self.buffer.resize(filled + READ_SIZE, 0);
+
+        loop {
+            match self.reader.read(&mut self.buffer[filled..]) {
+                Ok(read_count) => {
+                    self.buffer.truncate(filled + read_count);
+                    if read_count == 0 {
+                        self.eof = true;
+                    }
+                    return Ok(());
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.buffer.truncate(filled);
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// reader handing out a single byte per read, so every state has to survive a refill
+    struct ByteReader<'a> {
+        bytes: &'a [u8],
+    }
+
+    impl Read for ByteReader<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            if self.bytes.is_empty() || buffer.is_empty() {
+                return Ok(0);
+            }
+            buffer[0] = self.bytes[0];
+            self.bytes = &self.bytes[1..];
+            return Ok(1);
+        }
+    }
+
+    /// returns the first line and the content of every chunk of the data
+    fn chunks(data: &str, dialect: &Dialect, target_size: usize) -> Vec<(u64, String)> {
+        let mut chunk_reader = ChunkReader::new(
+            ByteReader {
+                bytes: data.as_bytes(),
+            },
+            dialect,
+        );
+        let mut chunks = Vec::new();
+        while let Some(chunk) = chunk_reader.next_chunk(target_size).unwrap() {
+            chunks.push((chunk.first_line, String::from_utf8(chunk.bytes).unwrap()));
+        }
+
+        return chunks;
+    }
+
+    #[test]
+    fn chunks_start_on_the_right_lines() {
+        let dialect = Dialect::new();
+        assert_eq!(
+            chunks("a\nb\nc\nd\ne\n", &dialect, 3),
+            vec![
+                (1, "a\nb\n".to_owned()),
+                (3, "c\nd\n".to_owned()),
+                (5, "e\n".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn quoted_line_break_across_the_target_size_is_kept() {
+        let dialect = Dialect::new();
+        assert_eq!(
+            chunks("a,b\n\"x\ny\",1\nc,2\n", &dialect, 6),
+            vec![(1, "a,b\n\"x\ny\",1\n".to_owned()), (4, "c,2\n".to_owned())]
+        );
+    }
+
+    #[test]
+    fn doubled_quotes_keep_the_field_quoted() {
+        let mut dialect = Dialect::new();
+        let data = "\"say \"\"hi\"\"\nthere\",1\nnext,2\n";
+        assert_eq!(
+            chunks(data, &dialect, 1),
+            vec![
+                (1, "\"say \"\"hi\"\"\nthere\",1\n".to_owned()),
+                (3, "next,2\n".to_owned()),
+            ]
+        );
+
+        // without doubled quotes the second quote ends the field
+        dialect.set_double_quote(false);
+        assert_eq!(chunks(data, &dialect, 1).len(), 3);
+    }
+
+    #[test]
+    fn escaped_quote_does_not_end_the_field() {
+        let mut dialect = Dialect::new();
+        dialect.set_escape(Some(b'\\'));
+        assert_eq!(
+            chunks("\"a\\\"\nb\",1\nc,2\n", &dialect, 1),
+            vec![(1, "\"a\\\"\nb\",1\n".to_owned()), (3, "c,2\n".to_owned())]
+        );
+    }
+
+    #[test]
+    fn quotes_in_comment_lines_are_ignored() {
+        let mut dialect = Dialect::new();
+        dialect.set_comment(Some(b'#'));
+        assert_eq!(
+            chunks("#a,\"b\nc,d\ne,f\n", &dialect, 1),
+            vec![
+                (1, "#a,\"b\n".to_owned()),
+                (2, "c,d\n".to_owned()),
+                (3, "e,f\n".to_owned()),
+            ]
+        );
+
+        // the same line starts a quoted field when comments are disabled
+        assert_eq!(
+            chunks("#a,\"b\nc,d\ne,f\n", &Dialect::new(), 1),
+            vec![(1, "#a,\"b\nc,d\ne,f\n".to_owned())]
+        );
+    }
+
+    #[test]
+    fn last_chunk_does_not_need_a_line_break() {
+        let dialect = Dialect::new();
+        assert_eq!(
+            chunks("a,b\n1,2", &dialect, 1),
+            vec![(1, "a,b\n".to_owned()), (2, "1,2".to_owned())]
+        );
+        assert_eq!(chunks("", &dialect, 1), Vec::new());
+    }
+
+    #[test]
+    fn peek_does_not_consume_the_stream() {
+        let dialect = Dialect::new();
+        let mut chunk_reader = ChunkReader::new("a,b\n1,2\n".as_bytes(), &dialect);
+        assert_eq!(chunk_reader.peek(6).unwrap(), b"a,b\n1,");
+        assert_eq!(chunk_reader.peek(100).unwrap(), b"a,b\n1,2\n");
+
+        let chunk = chunk_reader.next_chunk(100).unwrap().unwrap();
+        assert_eq!((chunk.first_line, chunk.bytes), (1, b"a,b\n1,2\n".to_vec()));
+        assert!(chunk_reader.next_chunk(100).unwrap().is_none());
+    }
+}
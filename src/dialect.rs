@@ -0,0 +1,206 @@
+//! stores the csv dialect (delimiter, quoting, escaping and comments) used to parse a source, and
+//! sniffs the delimiter from a sample when it is not known up front.
+
+/// delimiters tried when sniffing, in order of preference for ties
+const SNIFF_CANDIDATES: [u8; 5] = [b',', b';', b'\t', b'|', b':'];
+
+/// maximum number of records parsed from the sample per candidate delimiter
+const SNIFF_RECORDS: usize = 50;
+
+/// characters that control how a csv source is split into records and fields
+#[derive(Debug, Clone)]
+pub struct Dialect {
+    delimiter: u8,
+    quote: u8,
+    escape: Option<u8>,
+    double_quote: bool,
+    comment: Option<u8>,
+}
+
+impl Dialect {
+    /// returns a new Dialect for standard csv: comma delimiter, double quote quoting with doubled
+    /// quotes as escapes, no escape character and no comments
+    pub fn new() -> Self {
+        return Dialect {
+            delimiter: b',',
+            quote: b'"',
+            escape: None,
+            double_quote: true,
+            comment: None,
+        };
+    }
+
+    /// returns the field delimiter
+    pub fn get_delimiter(&self) -> u8 {
+        return self.delimiter;
+    }
+
+    /// returns the quote character
+    pub fn get_quote(&self) -> u8 {
+        return self.quote;
+    }
+
+    /// returns the escape character used inside quoted fields, if any
+    pub fn get_escape(&self) -> Option<u8> {
+        return self.escape;
+    }
+
+    /// returns whether two quote characters in a quoted field stand for one quote
+    pub fn get_double_quote(&self) -> bool {
+        return self.double_quote;
+    }
+
+    /// returns the character that starts comment lines, if any
+    pub fn get_comment(&self) -> Option<u8> {
+        return self.comment;
+    }
+
+    /// sets the field delimiter
+    pub fn set_delimiter(&mut self, delimiter: u8) {
+        self.delimiter = delimiter;
+    }
+
+    /// sets the quote character
+    pub fn set_quote(&mut self, quote: u8) {
+        self.quote = quote;
+    }
+
+    /// sets the escape character used inside quoted fields, None disables escaping
+    pub fn set_escape(&mut self, escape: Option<u8>) {
+        self.escape = escape;
+    }
+
+    /// sets whether two quote characters in a quoted field stand for one quote
+    pub fn set_double_quote(&mut self, double_quote: bool) {
+        self.double_quote = double_quote;
+    }
+
+    /// sets the character that starts comment lines, None disables comments
+    pub fn set_comment(&mut self, comment: Option<u8>) {
+        self.comment = comment;
+    }
+
+    /// returns a csv::ReaderBuilder configured with this dialect, without headers and accepting
+    /// records of any length
+    pub fn reader_builder(&self) -> csv::ReaderBuilder {
+        let mut builder = csv::ReaderBuilder::new();
+        builder
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .escape(self.escape)
+            .double_quote(self.double_quote)
+            .comment(self.comment);
+
+        return builder;
+    }
+
+    /// picks the delimiter that splits the sample into the most consistent number of fields per
+    /// record, keeping the current delimiter if no candidate splits records into several fields
+    pub fn sniff_delimiter(&mut self, sample: &[u8]) {
+        // only complete lines are used, the last one may be cut in the middle
+        let sample = match sample.iter().rposition(|byte| *byte == b'\n') {
+            Some(position) => &sample[..=position],
+            None => sample,
+        };
+
+        // consistency (share of records with the most common field count) and that field count
+        let mut best: Option<(f64, usize, u8)> = None;
+        for candidate in SNIFF_CANDIDATES {
+            let mut dialect = self.clone();
+            dialect.set_delimiter(candidate);
+
+            let field_counts: Vec<usize> = dialect
+                .reader_builder()
+                .from_reader(sample)
+                .records()
+                .take(SNIFF_RECORDS)
+                .filter_map(|record| record.ok())
+                .map(|record| record.len())
+                .collect();
+            if field_counts.is_empty() {
+                continue;
+            }
+
+            // find the most common field count
+            let mut common_count: usize = 0;
+            let mut common_frequency: usize = 0;
+            for field_count in field_counts.iter() {
+                let frequency = field_counts.iter().filter(|c| *c == field_count).count();
+                if frequency > common_frequency
+                    || (frequency == common_frequency && *field_count > common_count)
+                {
+                    common_count = *field_count;
+                    common_frequency = frequency;
+                }
+            }
+            if common_count < 2 {
+                continue;
+            }
+
+            let consistency = common_frequency as f64 / field_counts.len() as f64;
+            let is_better = match best {
+                None => true,
+                Some((best_consistency, best_count, _)) => {
+                    consistency > best_consistency
+                        || (consistency == best_consistency && common_count > best_count)
+                }
+            };
+            if is_better {
+                best = Some((consistency, common_count, candidate));
+            }
+        }
+
+        if let Some((_, _, delimiter)) = best {
+            self.delimiter = delimiter;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// returns the delimiter sniffed from the sample by a standard dialect
+    fn sniff(sample: &str) -> u8 {
+        let mut dialect = Dialect::new();
+        dialect.sniff_delimiter(sample.as_bytes());
+
+        return dialect.get_delimiter();
+    }
+
+    #[test]
+    fn sniff_delimiter_finds_common_delimiters() {
+        assert_eq!(sniff("a,b,c\n1,2,3\n4,5,6\n"), b',');
+        assert_eq!(sniff("a;b;c\n1,5;2;3\n4;5,5;6\n"), b';');
+        assert_eq!(sniff("a\tb\tc\n1\t2\t3\n4\t5\t6\n"), b'\t');
+        assert_eq!(sniff("a|b|c\n1|2|3\n4|5|6\n"), b'|');
+    }
+
+    #[test]
+    fn sniff_delimiter_ignores_delimiters_in_quoted_fields() {
+        // counted naively the commas would split every line into three fields
+        let sample = "name;city\n\"Smith, John\";\"Berlin, DE\"\n\"Doe, Jane\";\"Paris, FR\"\n";
+        assert_eq!(sniff(sample), b';');
+        assert_eq!(sniff("a,b\n\"x;y;z\",1\n\"p;q\",2\n"), b',');
+    }
+
+    #[test]
+    fn sniff_delimiter_accepts_ragged_records() {
+        assert_eq!(sniff("a;b;c\n1,5;2;3\n4;5\n6;7;8,1\n"), b';');
+        // the last line of the sample may be cut in the middle
+        assert_eq!(sniff("a|b|c\n1|2|3\n4|5|6\n7|8"), b'|');
+    }
+
+    #[test]
+    fn sniff_delimiter_keeps_the_delimiter_without_a_split() {
+        assert_eq!(sniff("name\nalpha\nbeta\n"), b',');
+        assert_eq!(sniff(""), b',');
+
+        let mut dialect = Dialect::new();
+        dialect.set_delimiter(b';');
+        dialect.sniff_delimiter(b"name\nalpha\nbeta\n");
+        assert_eq!(dialect.get_delimiter(), b';');
+    }
+}
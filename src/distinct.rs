@@ -0,0 +1,272 @@
+//! counts the distinct raw values of a column. Every value is counted exactly until the column has
+//! more distinct values than a threshold, then a HyperLogLog sketch estimates the distinct count
+//! and only the most frequent values keep a count.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// number of distinct values counted exactly unless the user passes their own threshold
+pub const DEFAULT_DISTINCT_THRESHOLD: usize = 10_000;
+
+/// number of hash bits used to pick a register, the sketch keeps 2^14 registers
+const HYPERLOGLOG_PRECISION: u32 = 14;
+
+/// HyperLogLog sketch of a set of strings. Every register keeps the longest run of leading zeros
+/// seen in the hashes routed to it, which grows with the logarithm of the number of distinct values
+pub(crate) struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// returns a new HyperLogLog without any values
+    pub(crate) fn new() -> Self {
+        return HyperLogLog {
+            registers: vec![0; 1 << HYPERLOGLOG_PRECISION],
+        };
+    }
+
+    /// returns the relative standard error of the estimate
+    pub(crate) fn get_error() -> f64 {
+        return 1.04 / ((1u64 << HYPERLOGLOG_PRECISION) as f64).sqrt();
+    }
+
+    /// adds one value
+    pub(crate) fn add(&mut self, value: &str) {
+        // the default hasher uses fixed keys, so every worker hashes a value the same way
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let index = (hash >> (64 - HYPERLOGLOG_PRECISION)) as usize;
+        let rest = hash << HYPERLOGLOG_PRECISION;
+        let rank = (rest.leading_zeros() + 1).min(64 - HYPERLOGLOG_PRECISION + 1) as u8;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// adds the values of another sketch of the same column
+    pub(crate) fn merge(&mut self, other: &HyperLogLog) {
+        for (register, other_register) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *register = (*register).max(*other_register);
+        }
+    }
+
+    /// returns the estimated number of distinct values added
+    pub(crate) fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let inverse_sum: f64 = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-(*register as i32)))
+            .sum();
+        let estimate = alpha * m * m / inverse_sum;
+
+        // small sets leave registers empty, linear counting is more accurate for them
+        let empty_registers = self.registers.iter().filter(|r| **r == 0).count();
+        if estimate <= 2.5 * m && empty_registers > 0 {
+            return (m * (m / empty_registers as f64).ln()).round() as u64;
+        }
+        return estimate.round() as u64;
+    }
+}
+
+/// counts of the raw values of a column. Exact up to threshold distinct values, after that a
+/// sketch estimates the distinct count and only frequent values keep a count, as a lower bound
+pub(crate) struct ValueCounter {
+    counts: HashMap<String, u64>,
+    sketch: Option<HyperLogLog>,
+    threshold: usize,
+    // total taken off the counts while shrinking, no value without a count appears more often
+    decrement: u64,
+}
+
+impl ValueCounter {
+    /// returns a new ValueCounter that counts up to threshold distinct values exactly
+    pub(crate) fn new(threshold: usize) -> Self {
+        return ValueCounter {
+            counts: HashMap::new(),
+            sketch: None,
+            threshold,
+            decrement: 0,
+        };
+    }
+
+    /// counts one value
+    pub(crate) fn add(&mut self, value: &str) {
+        if let Some(sketch) = &mut self.sketch {
+            sketch.add(value);
+        }
+
+        // allocate a key only for values not seen before
+        match self.counts.get_mut(value) {
+            Some(count) => *count += 1,
+            None => {
+                self.counts.insert(value.to_owned(), 1);
+                if self.counts.len() > self.threshold {
+                    self.shrink();
+                }
+            }
+        }
+    }
+
+    /// adds the counts of another part of the same column
+    pub(crate) fn merge(&mut self, other: ValueCounter) {
+        match (&mut self.sketch, &other.sketch) {
+            (Some(sketch), Some(other_sketch)) => sketch.merge(other_sketch),
+            (Some(sketch), None) => other.counts.keys().for_each(|value| sketch.add(value)),
+            (None, Some(other_sketch)) => {
+                let mut sketch = HyperLogLog::new();
+                self.counts.keys().for_each(|value| sketch.add(value));
+                sketch.merge(other_sketch);
+                self.sketch = Some(sketch);
+            }
+            (None, None) => {}
+        }
+
+        // a value missing from one part may have been taken off its counts there
+        self.decrement += other.decrement;
+        for (value, count) in other.counts {
+            *self.counts.entry(value).or_insert(0) += count;
+        }
+        if self.counts.len() > self.threshold {
+            self.shrink();
+        }
+    }
+
+    /// switches to the sketch if needed and drops the least frequent values. The count of the value
+    /// in the middle is taken off every count, which drops at least half of the values while the
+    /// frequent ones keep most of their count. When all counts are equal every value is dropped
+    fn shrink(&mut self) {
+        if self.sketch.is_none() {
+            let mut sketch = HyperLogLog::new();
+            self.counts.keys().for_each(|value| sketch.add(value));
+            self.sketch = Some(sketch);
+        }
+
+        let mut counts: Vec<u64> = self.counts.values().copied().collect();
+        let middle = counts.len() / 2;
+        let (_, middle_count, _) = counts.select_nth_unstable_by(middle, |a, b| b.cmp(a));
+        let middle_count = *middle_count;
+        self.decrement += middle_count;
+        self.counts.retain(|_, count| {
+            *count = count.saturating_sub(middle_count);
+            return *count > 0;
+        });
+    }
+
+    /// returns the number of distinct values, estimated once the threshold was passed
+    pub(crate) fn get_distinct_count(&self) -> u64 {
+        return match &self.sketch {
+            Some(sketch) => sketch.estimate(),
+            None => self.counts.len() as u64,
+        };
+    }
+
+    /// returns the relative standard error of the distinct count, None if it is exact
+    pub(crate) fn get_distinct_error(&self) -> Option<f64> {
+        return self.sketch.as_ref().map(|_| HyperLogLog::get_error());
+    }
+
+    /// returns the counted values with their counts, ordered by value. Once the threshold was
+    /// passed only frequent values are returned, with counts that are lower bounds
+    pub(crate) fn get_counts(&self) -> Vec<(String, u64)> {
+        let mut counts: Vec<(String, u64)> = self
+            .counts
+            .iter()
+            .map(|(value, count)| (value.clone(), *count))
+            .collect();
+        counts.sort();
+        return counts;
+    }
+
+    /// consumes the counter and returns the distinct values, empty once the threshold was passed
+    pub(crate) fn into_values(self) -> HashSet<String> {
+        if self.sketch.is_some() {
+            return HashSet::new();
+        }
+        return self.counts.into_keys().collect();
+    }
+
+    /// returns the k most frequent values with their counts, most frequent first. Values with equal
+    /// counts are ordered by value. Once counts were shrunk, only values known to appear more often
+    /// than any value without a count are returned, none if the column has no frequent values
+    pub(crate) fn get_top_values(&self, k: usize) -> Vec<(String, u64)> {
+        let mut value_counts: Vec<(&String, &u64)> = self
+            .counts
+            .iter()
+            .filter(|(_, count)| **count > self.decrement)
+            .collect();
+        value_counts.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        return value_counts
+            .into_iter()
+            .take(k)
+            .map(|(value, count)| (value.clone(), *count))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hyperloglog_estimate_is_within_two_percent() {
+        let mut first = HyperLogLog::new();
+        let mut second = HyperLogLog::new();
+        for i in 0..100_000 {
+            let value = format!("value-{}", i);
+            first.add(&value);
+            // values seen by both parts are counted once
+            if i % 4 == 0 {
+                second.add(&value);
+            }
+        }
+        first.merge(&second);
+
+        let estimate = first.estimate() as f64;
+        assert!(
+            (estimate - 100_000.0).abs() / 100_000.0 <= 0.02,
+            "{}",
+            estimate
+        );
+    }
+
+    #[test]
+    fn hyperloglog_counts_small_sets_with_linear_counting() {
+        let mut sketch = HyperLogLog::new();
+        assert_eq!(sketch.estimate(), 0);
+        (0..100).for_each(|i| sketch.add(&i.to_string()));
+        (0..100).for_each(|i| sketch.add(&i.to_string()));
+        assert_eq!(sketch.estimate(), 100);
+    }
+
+    #[test]
+    fn value_counter_has_no_top_values_for_unique_values() {
+        let mut counter = ValueCounter::new(100);
+        (0..10_000).for_each(|i| counter.add(&i.to_string()));
+        assert!(counter.get_distinct_error().is_some());
+        assert!(counter.get_top_values(5).is_empty());
+    }
+
+    #[test]
+    fn value_counter_keeps_frequent_values_past_the_threshold() {
+        let mut first = ValueCounter::new(100);
+        let mut second = ValueCounter::new(100);
+        for i in 0..10_000 {
+            let counter = if i % 2 == 0 { &mut first } else { &mut second };
+            counter.add(&i.to_string());
+            if i % 10 == 0 {
+                counter.add("frequent");
+            }
+        }
+        first.merge(second);
+
+        let top_values = first.get_top_values(5);
+        assert_eq!(top_values.len(), 1);
+        assert_eq!(top_values[0].0, "frequent");
+        assert!(top_values[0].1 <= 1000);
+    }
+}
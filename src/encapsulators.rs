@@ -1,87 +1,590 @@
-//! stores encapsulation objects that organize summary values for different column types.
+//! stores encapsulation objects that organize summary values for different column types. These
+//! are the values returned by the Summarizer.
 
-use chrono::NaiveDate;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeDelta};
 use std::collections::HashSet;
 
 // stores value from one field
+#[derive(Debug, Clone)]
 pub enum ColumnType {
     Text(String),
+    Integer(i64),
     Float(f64),
     Date(NaiveDate),
-    FileEnd,
+    // date with the year last, as first number, second number and year. Whether the first number
+    // is the day or the month is decided for the whole column
+    UnorderedDate(u32, u32, i32),
+    // local date and time as written, with the utc offset if the value has one
+    DateTime(NaiveDateTime, Option<FixedOffset>),
+    // number written with a currency or percent sign, like $12.00 or 45%
+    Quantity(f64, NumberUnit),
+    Boolean(bool),
+    // missing value, excluded from type inference and statistics
+    Null,
+}
+
+// kind of a single non-null value, tallied per column to infer the column type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueKind {
+    Integer,
+    Float,
+    Date,
+    DateTime,
+    Boolean,
+    Text,
+}
+
+impl ValueKind {
+    /// all kinds, in the order used to index per kind counts
+    pub const ALL: [ValueKind; 6] = [
+        ValueKind::Integer,
+        ValueKind::Float,
+        ValueKind::Date,
+        ValueKind::DateTime,
+        ValueKind::Boolean,
+        ValueKind::Text,
+    ];
+
+    /// returns the position of the kind in ValueKind::ALL
+    pub fn get_index(&self) -> usize {
+        return *self as usize;
+    }
+
+    /// returns the lowercase name of the kind
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            ValueKind::Integer => "integer",
+            ValueKind::Float => "float",
+            ValueKind::Date => "date",
+            ValueKind::DateTime => "datetime",
+            ValueKind::Boolean => "boolean",
+            ValueKind::Text => "text",
+        };
+    }
+}
+
+// order of day and month in dates written with the year last
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateOrder {
+    // (d)d-(m)m-yyyy
+    DayFirst,
+    // (m)m-(d)d-yyyy
+    MonthFirst,
+}
+
+impl DateOrder {
+    /// returns the short name of the order, dmy or mdy
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            DateOrder::DayFirst => "dmy",
+            DateOrder::MonthFirst => "mdy",
+        };
+    }
+
+    /// returns the date written as the passed first and second number and year in this order, or
+    /// None if there is no such date
+    pub fn build_date(&self, first: u32, second: u32, year: i32) -> Option<NaiveDate> {
+        return match self {
+            DateOrder::DayFirst => NaiveDate::from_ymd_opt(year, second, first),
+            DateOrder::MonthFirst => NaiveDate::from_ymd_opt(year, first, second),
+        };
+    }
+}
+
+// meaning of a text value recognized from its format
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SemanticType {
+    Email,
+    Url,
+    Uuid,
+    Ipv4,
+    Ipv6,
+    Phone,
+    Currency,
+    CountryCode,
+}
+
+impl SemanticType {
+    /// all semantic types, in the order used to index per type counts
+    pub const ALL: [SemanticType; 8] = [
+        SemanticType::Email,
+        SemanticType::Url,
+        SemanticType::Uuid,
+        SemanticType::Ipv4,
+        SemanticType::Ipv6,
+        SemanticType::Phone,
+        SemanticType::Currency,
+        SemanticType::CountryCode,
+    ];
+
+    /// returns the position of the semantic type in SemanticType::ALL
+    pub fn get_index(&self) -> usize {
+        return *self as usize;
+    }
+
+    /// returns the lowercase name of the semantic type
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            SemanticType::Email => "email",
+            SemanticType::Url => "url",
+            SemanticType::Uuid => "uuid",
+            SemanticType::Ipv4 => "ipv4",
+            SemanticType::Ipv6 => "ipv6",
+            SemanticType::Phone => "phone",
+            SemanticType::Currency => "currency",
+            SemanticType::CountryCode => "country",
+        };
+    }
+}
+
+// unit written next to a number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NumberUnit {
+    // value followed by %, kept as written so 45% is 45
+    Percent,
+    // currency symbol or code, like $ or EUR
+    Currency(&'static str),
+}
+
+impl NumberUnit {
+    /// returns the sign of the unit as written, % or the currency symbol or code
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            NumberUnit::Percent => "%",
+            NumberUnit::Currency(sign) => sign,
+        };
+    }
+}
+
+// stores counts shared by columns of every type
+#[derive(Debug, Clone)]
+pub struct ColumnProfile {
+    row_count: u64,
+    null_count: u64,
+    type_counts: [u64; ValueKind::ALL.len()],
+    non_conforming_count: u64,
+    non_conforming_examples: Vec<String>,
+    // most frequent raw values with their counts, most frequent first
+    top_values: Vec<(String, u64)>,
+}
+
+impl ColumnProfile {
+    /// returns a new ColumnProfile with all counts set to 0 and no examples
+    pub fn new() -> Self {
+        return ColumnProfile {
+            row_count: 0,
+            null_count: 0,
+            type_counts: [0; ValueKind::ALL.len()],
+            non_conforming_count: 0,
+            non_conforming_examples: vec![],
+            top_values: vec![],
+        };
+    }
+
+    /// returns the number of rows in the column, including nulls
+    pub fn get_row_count(&self) -> u64 {
+        return self.row_count;
+    }
+
+    /// returns the number of null values in the column
+    pub fn get_null_count(&self) -> u64 {
+        return self.null_count;
+    }
+
+    /// returns the share of null values in the column as a percentage, 0.0 for a column without
+    /// rows
+    pub fn get_null_percentage(&self) -> f64 {
+        if self.row_count == 0 {
+            return 0.0;
+        }
+        return self.null_count as f64 / self.row_count as f64 * 100.0;
+    }
+
+    /// sets the row_count field
+    pub fn set_row_count(&mut self, row_count: u64) {
+        self.row_count = row_count;
+    }
+
+    /// sets the null_count field
+    pub fn set_null_count(&mut self, null_count: u64) {
+        self.null_count = null_count;
+    }
+
+    /// returns the number of values in the column that parsed as the passed kind
+    pub fn get_type_count(&self, kind: ValueKind) -> u64 {
+        return self.type_counts[kind.get_index()];
+    }
+
+    /// sets the number of values in the column that parsed as the passed kind
+    pub fn set_type_count(&mut self, kind: ValueKind, count: u64) {
+        self.type_counts[kind.get_index()] = count;
+    }
+
+    /// returns the number of non-null values that do not match the inferred column type
+    pub fn get_non_conforming_count(&self) -> u64 {
+        return self.non_conforming_count;
+    }
+
+    /// returns a few of the values that do not match the inferred column type
+    pub fn get_non_conforming_examples(&self) -> Vec<String> {
+        return self.non_conforming_examples.clone();
+    }
+
+    /// returns the most frequent values as written in the source, with their counts and their share
+    /// of all rows as a percentage, most frequent first. Empty when the column has more distinct
+    /// values than the distinct threshold and none of them is known to be frequent
+    pub fn get_top_values(&self) -> Vec<(String, u64, f64)> {
+        return self
+            .top_values
+            .iter()
+            .map(|(value, count)| {
+                let percentage = if self.row_count == 0 {
+                    0.0
+                } else {
+                    *count as f64 / self.row_count as f64 * 100.0
+                };
+                (value.clone(), *count, percentage)
+            })
+            .collect();
+    }
+
+    /// returns the most frequent value, None for a column without values or with too many distinct
+    /// values to tell which is the most frequent
+    pub fn get_mode(&self) -> Option<String> {
+        return self.top_values.first().map(|(value, _)| value.clone());
+    }
+
+    /// sets the most frequent values with their counts, most frequent first
+    pub fn set_top_values(&mut self, top_values: Vec<(String, u64)>) {
+        self.top_values = top_values;
+    }
+
+    /// sets the non_conforming_count field
+    pub fn set_non_conforming_count(&mut self, non_conforming_count: u64) {
+        self.non_conforming_count = non_conforming_count;
+    }
+
+    /// sets the non_conforming_examples field
+    pub fn set_non_conforming_examples(&mut self, non_conforming_examples: Vec<String>) {
+        self.non_conforming_examples = non_conforming_examples;
+    }
 }
 
 // stores summary of a column with text values
+#[derive(Debug, Clone)]
 pub struct TextColumn {
+    // distinct values, only kept while they are counted exactly
     categories: HashSet<String>,
-    category_count: u16,
+    category_count: u64,
+    category_count_error: Option<f64>,
+    min_length: u64,
+    max_length: u64,
+    mean_length: f64,
+    empty_count: u64,
+    leading_whitespace_count: u64,
+    trailing_whitespace_count: u64,
+    non_ascii_count: u64,
+    // values the shape masks and the semantic type were taken from
+    profiled_count: u64,
+    pattern_count: u64,
+    untracked_pattern_count: u64,
+    top_patterns: Vec<(String, u64, String)>,
+    semantic_type: Option<(SemanticType, f64)>,
+    profile: ColumnProfile,
 }
 
 impl TextColumn {
-    /// returns new TextColumn object with empty categories set and category_count set to 0
+    /// returns new TextColumn object with empty categories set and category_count, lengths and
+    /// counts set to 0
     pub fn new() -> Self {
         return TextColumn {
             categories: HashSet::new(),
             category_count: 0,
+            category_count_error: None,
+            min_length: 0,
+            max_length: 0,
+            mean_length: 0.0,
+            empty_count: 0,
+            leading_whitespace_count: 0,
+            trailing_whitespace_count: 0,
+            non_ascii_count: 0,
+            profiled_count: 0,
+            pattern_count: 0,
+            untracked_pattern_count: 0,
+            top_patterns: vec![],
+            semantic_type: None,
+            profile: ColumnProfile::new(),
         };
     }
 
-    /// method that checks is a value is already in categories vector.
-    /// if not, it adds the passed value to categories HashSet and increases category_count by 1.
-    pub fn add_to_categories(&mut self, value: String) {
-        if !self.categories.contains(&value) {
-            self.categories.insert(value);
-            self.category_count += 1;
-        }
-    }
-
     /// method that returns a new TextColumn object from an existing one with the same values. Mostly used to get a
     /// TextColumn object out of other encapsulations
     pub fn build_summary(&self) -> TextColumn {
         let mut text_column_summary = TextColumn::new();
         text_column_summary.set_categories(self.categories.clone());
         text_column_summary.set_category_count(self.category_count);
+        text_column_summary.set_category_count_error(self.category_count_error);
+        text_column_summary.set_min_length(self.min_length);
+        text_column_summary.set_max_length(self.max_length);
+        text_column_summary.set_mean_length(self.mean_length);
+        text_column_summary.set_empty_count(self.empty_count);
+        text_column_summary.set_leading_whitespace_count(self.leading_whitespace_count);
+        text_column_summary.set_trailing_whitespace_count(self.trailing_whitespace_count);
+        text_column_summary.set_non_ascii_count(self.non_ascii_count);
+        text_column_summary.set_profiled_count(self.profiled_count);
+        text_column_summary.set_pattern_count(self.pattern_count);
+        text_column_summary.set_untracked_pattern_count(self.untracked_pattern_count);
+        text_column_summary.set_top_patterns(self.top_patterns.clone());
+        text_column_summary.set_semantic_type(self.semantic_type);
+        text_column_summary.set_profile(self.get_profile());
 
         return text_column_summary;
     }
 
+    /// method that checks is a value is already in categories vector.
+    /// if not, it adds the passed value to categories HashSet and increases category_count by 1.
+    pub fn add_to_categories(&mut self, value: String) {
+        if !self.categories.contains(&value) {
+            self.categories.insert(value);
+            self.category_count += 1;
+        }
+    }
+
     /// method that sets categories field to a HashSet passed in this method.
     pub fn set_categories(&mut self, categories: HashSet<String>) {
         self.categories = categories;
     }
 
+    /// method that returns categories field using clone(). Empty when the column has more distinct
+    /// values than the distinct threshold, use get_category_count for their number
+    pub fn get_categories(&self) -> HashSet<String> {
+        return self.categories.clone();
+    }
+
     /// method that sets category count to a number passed in this method.
-    pub fn set_category_count(&mut self, category_count: u16) {
+    pub fn set_category_count(&mut self, category_count: u64) {
         self.category_count = category_count;
     }
 
-    /// method that returns categories field using clone().
-    pub fn get_categories(&self) -> HashSet<String> {
-        return self.categories.clone();
+    /// method that returns the number of distinct values from a TextColumn object, estimated for
+    /// columns with more distinct values than the distinct threshold
+    pub fn get_category_count(&self) -> u64 {
+        return self.category_count;
     }
 
-    /// method that returns category_count from a TextColumn object.
-    pub fn get_category_count(&self) -> u16 {
-        return self.category_count;
+    /// sets the relative standard error of the category count, None if it is exact
+    pub fn set_category_count_error(&mut self, category_count_error: Option<f64>) {
+        self.category_count_error = category_count_error;
+    }
+
+    /// returns the relative standard error of the category count, None if it is exact
+    pub fn get_category_count_error(&self) -> Option<f64> {
+        return self.category_count_error;
+    }
+
+    /// returns the length in characters of the shortest non-null value, 0 without values
+    pub fn get_min_length(&self) -> u64 {
+        return self.min_length;
+    }
+
+    /// sets the min_length field
+    pub fn set_min_length(&mut self, min_length: u64) {
+        self.min_length = min_length;
+    }
+
+    /// returns the length in characters of the longest non-null value, 0 without values
+    pub fn get_max_length(&self) -> u64 {
+        return self.max_length;
+    }
+
+    /// sets the max_length field
+    pub fn set_max_length(&mut self, max_length: u64) {
+        self.max_length = max_length;
+    }
+
+    /// returns the mean length in characters of the non-null values, 0.0 without values
+    pub fn get_mean_length(&self) -> f64 {
+        return self.mean_length;
+    }
+
+    /// sets the mean_length field
+    pub fn set_mean_length(&mut self, mean_length: f64) {
+        self.mean_length = mean_length;
+    }
+
+    /// returns the number of empty fields, including the ones counted as nulls
+    pub fn get_empty_count(&self) -> u64 {
+        return self.empty_count;
+    }
+
+    /// sets the empty_count field
+    pub fn set_empty_count(&mut self, empty_count: u64) {
+        self.empty_count = empty_count;
+    }
+
+    /// returns the number of values starting with whitespace
+    pub fn get_leading_whitespace_count(&self) -> u64 {
+        return self.leading_whitespace_count;
+    }
+
+    /// sets the leading_whitespace_count field
+    pub fn set_leading_whitespace_count(&mut self, leading_whitespace_count: u64) {
+        self.leading_whitespace_count = leading_whitespace_count;
+    }
+
+    /// returns the number of values ending with whitespace
+    pub fn get_trailing_whitespace_count(&self) -> u64 {
+        return self.trailing_whitespace_count;
+    }
+
+    /// sets the trailing_whitespace_count field
+    pub fn set_trailing_whitespace_count(&mut self, trailing_whitespace_count: u64) {
+        self.trailing_whitespace_count = trailing_whitespace_count;
+    }
+
+    /// returns the number of values with characters outside ASCII
+    pub fn get_non_ascii_count(&self) -> u64 {
+        return self.non_ascii_count;
+    }
+
+    /// sets the non_ascii_count field
+    pub fn set_non_ascii_count(&mut self, non_ascii_count: u64) {
+        self.non_ascii_count = non_ascii_count;
+    }
+
+    /// returns true if all values of the column are pure ASCII
+    pub fn is_ascii(&self) -> bool {
+        return self.non_ascii_count == 0;
+    }
+
+    /// returns the number of values the shape masks and the semantic type were taken from, all
+    /// values unless the column had more distinct values than the distinct threshold
+    pub fn get_profiled_count(&self) -> u64 {
+        return self.profiled_count;
+    }
+
+    /// sets the profiled_count field
+    pub fn set_profiled_count(&mut self, profiled_count: u64) {
+        self.profiled_count = profiled_count;
+    }
+
+    /// returns the number of distinct shape masks, at most 1000 masks are counted
+    pub fn get_pattern_count(&self) -> u64 {
+        return self.pattern_count;
+    }
+
+    /// sets the pattern_count field
+    pub fn set_pattern_count(&mut self, pattern_count: u64) {
+        self.pattern_count = pattern_count;
+    }
+
+    /// returns the number of values whose shape mask was not counted because too many masks were
+    /// found
+    pub fn get_untracked_pattern_count(&self) -> u64 {
+        return self.untracked_pattern_count;
+    }
+
+    /// sets the untracked_pattern_count field
+    pub fn set_untracked_pattern_count(&mut self, untracked_pattern_count: u64) {
+        self.untracked_pattern_count = untracked_pattern_count;
+    }
+
+    /// returns the most frequent shape masks (A for uppercase letters, a for other letters, 9 for
+    /// digits) with their counts, their share of the profiled values as a percentage and an
+    /// example value, most frequent first
+    pub fn get_top_patterns(&self) -> Vec<(String, u64, f64, String)> {
+        return self
+            .top_patterns
+            .iter()
+            .map(|(pattern, count, example)| {
+                let percentage = if self.profiled_count == 0 {
+                    0.0
+                } else {
+                    *count as f64 / self.profiled_count as f64 * 100.0
+                };
+                (pattern.clone(), *count, percentage, example.clone())
+            })
+            .collect();
+    }
+
+    /// sets the most frequent shape masks with their counts and example values, most frequent first
+    pub fn set_top_patterns(&mut self, top_patterns: Vec<(String, u64, String)>) {
+        self.top_patterns = top_patterns;
+    }
+
+    /// returns the semantic type detected for the column with the percentage of profiled values
+    /// matching it, None if no semantic type is matched by enough values
+    pub fn get_semantic_type(&self) -> Option<(SemanticType, f64)> {
+        return self.semantic_type;
+    }
+
+    /// sets the semantic_type field
+    pub fn set_semantic_type(&mut self, semantic_type: Option<(SemanticType, f64)>) {
+        self.semantic_type = semantic_type;
+    }
+
+    /// method that returns the row and null counts of the column.
+    pub fn get_profile(&self) -> ColumnProfile {
+        return self.profile.clone();
+    }
+
+    /// method that sets the row and null counts of the column.
+    pub fn set_profile(&mut self, profile: ColumnProfile) {
+        self.profile = profile;
     }
 }
 
 // stores summary of a column with number values
+#[derive(Debug, Clone)]
 pub struct NumberColumn {
+    count: u64,
     sum: f64,
+    min: f64,
+    max: f64,
+    // line numbers in the source of the first rows holding min and max
+    min_line: u64,
+    max_line: u64,
     mean: f64,
     median: f64,
-    std: f64,
+    // interquartile range, p75 - p25
+    iqr: f64,
+    // requested percentiles (0 to 100) with their values
+    percentiles: Vec<(f64, f64)>,
+    // normalized rank error of approximate percentiles, None when they are exact
+    rank_error: Option<f64>,
+    // values at the lower and upper edge of the rank error of every percentile
+    percentile_bounds: Vec<(f64, f64)>,
+    // None when undefined, like the sample variance of a single value
+    variance: Option<f64>,
+    std: Option<f64>,
+    // most common unit written next to the values, None for plain numbers
+    unit: Option<NumberUnit>,
+    // equal width bins from min to max with their lower and upper edge and number of values
+    histogram: Vec<(f64, f64, u64)>,
+    profile: ColumnProfile,
 }
 
 impl NumberColumn {
     /// returns a new NumberColumn object with all values set to 0.0
     pub fn new() -> Self {
         return NumberColumn {
+            count: 0,
             sum: 0.0,
+            min: 0.0,
+            max: 0.0,
+            min_line: 0,
+            max_line: 0,
             mean: 0.0,
             median: 0.0,
-            std: 0.0,
+            iqr: 0.0,
+            percentiles: vec![],
+            rank_error: None,
+            percentile_bounds: vec![],
+            variance: None,
+            std: None,
+            unit: None,
+            histogram: vec![],
+            profile: ColumnProfile::new(),
         };
     }
 
@@ -89,19 +592,62 @@ impl NumberColumn {
     /// NumberColumn object out of other encapsulation
     pub fn build_summary(&self) -> NumberColumn {
         let mut number_column_summary = NumberColumn::new();
+        number_column_summary.set_count(self.get_count());
         number_column_summary.set_sum(self.get_sum());
+        number_column_summary.set_min(self.get_min(), self.get_min_line());
+        number_column_summary.set_max(self.get_max(), self.get_max_line());
         number_column_summary.set_mean(self.get_mean());
         number_column_summary.set_median(self.get_median());
+        number_column_summary.set_iqr(self.get_iqr());
+        number_column_summary.set_percentiles(self.get_percentiles());
+        number_column_summary.set_rank_error(self.get_rank_error());
+        number_column_summary.set_percentile_bounds(self.get_percentile_bounds());
+        number_column_summary.set_variance(self.get_variance());
         number_column_summary.set_std(self.get_std());
+        number_column_summary.set_unit(self.get_unit());
+        number_column_summary.set_histogram(self.get_histogram());
+        number_column_summary.set_profile(self.get_profile());
 
         return number_column_summary;
     }
 
+    /// returns the number of number values in the column
+    pub fn get_count(&self) -> u64 {
+        return self.count;
+    }
+
     /// returns sum field from the object
     pub fn get_sum(&self) -> f64 {
         return self.sum;
     }
 
+    /// returns the smallest value of the column
+    pub fn get_min(&self) -> f64 {
+        return self.min;
+    }
+
+    /// returns the largest value of the column
+    pub fn get_max(&self) -> f64 {
+        return self.max;
+    }
+
+    /// returns the difference between the largest and the smallest value
+    pub fn get_range(&self) -> f64 {
+        return self.max - self.min;
+    }
+
+    /// returns the line number in the source of the first row holding the smallest value. Rows
+    /// spanning several lines are counted by their first line
+    pub fn get_min_line(&self) -> u64 {
+        return self.min_line;
+    }
+
+    /// returns the line number in the source of the first row holding the largest value. Rows
+    /// spanning several lines are counted by their first line
+    pub fn get_max_line(&self) -> u64 {
+        return self.max_line;
+    }
+
     /// returns mean field from the object
     pub fn get_mean(&self) -> f64 {
         return self.mean;
@@ -112,16 +658,74 @@ impl NumberColumn {
         return self.median;
     }
 
-    /// returns standard deviation field from the object
-    pub fn get_std(&self) -> f64 {
+    /// returns the interquartile range, the difference between the 75th and 25th percentile
+    pub fn get_iqr(&self) -> f64 {
+        return self.iqr;
+    }
+
+    /// returns the requested percentiles (0 to 100) with their values, in the requested order
+    pub fn get_percentiles(&self) -> Vec<(f64, f64)> {
+        return self.percentiles.clone();
+    }
+
+    /// returns the share of all values (0.0 to 1.0) that the rank of the median and percentiles can
+    /// be off by with 99% confidence, None when they are exact
+    pub fn get_rank_error(&self) -> Option<f64> {
+        return self.rank_error;
+    }
+
+    /// returns the lowest and highest value every percentile can have given the rank error, in the
+    /// order of get_percentiles. Empty when the percentiles are exact
+    pub fn get_percentile_bounds(&self) -> Vec<(f64, f64)> {
+        return self.percentile_bounds.clone();
+    }
+
+    /// returns the value at the percentile (0 to 100), None if it was not requested
+    pub fn get_percentile(&self, percentile: f64) -> Option<f64> {
+        return self
+            .percentiles
+            .iter()
+            .find(|(p, _)| *p == percentile)
+            .map(|(_, value)| *value);
+    }
+
+    /// returns the variance of the column, None when it is undefined
+    pub fn get_variance(&self) -> Option<f64> {
+        return self.variance;
+    }
+
+    /// returns standard deviation field from the object, None when it is undefined
+    pub fn get_std(&self) -> Option<f64> {
         return self.std;
     }
 
+    /// returns the row and null counts of the column
+    pub fn get_profile(&self) -> ColumnProfile {
+        return self.profile.clone();
+    }
+
+    /// sets the count field
+    pub fn set_count(&mut self, count: u64) {
+        self.count = count;
+    }
+
     /// sets the sum field
     pub fn set_sum(&mut self, sum: f64) {
         self.sum = sum;
     }
 
+    /// sets the smallest value and the line of the first row holding it
+    pub fn set_min(&mut self, min: f64, min_line: u64) {
+        self.min = min;
+        self.min_line = min_line;
+    }
+
+    /// sets the largest value and the line of the first row holding it
+    pub fn set_max(&mut self, max: f64, max_line: u64) {
+        self.max = max;
+        self.max_line = max_line;
+    }
+
     /// sets the mean field
     pub fn set_mean(&mut self, mean: f64) {
         self.mean = mean;
@@ -132,16 +736,226 @@ impl NumberColumn {
         self.median = median;
     }
 
+    /// sets the interquartile range
+    pub fn set_iqr(&mut self, iqr: f64) {
+        self.iqr = iqr;
+    }
+
+    /// sets the percentiles (0 to 100) with their values
+    pub fn set_percentiles(&mut self, percentiles: Vec<(f64, f64)>) {
+        self.percentiles = percentiles;
+    }
+
+    /// sets the rank error of the percentiles, None when they are exact
+    pub fn set_rank_error(&mut self, rank_error: Option<f64>) {
+        self.rank_error = rank_error;
+    }
+
+    /// sets the lowest and highest value of every percentile
+    pub fn set_percentile_bounds(&mut self, percentile_bounds: Vec<(f64, f64)>) {
+        self.percentile_bounds = percentile_bounds;
+    }
+
+    /// sets the variance field
+    pub fn set_variance(&mut self, variance: Option<f64>) {
+        self.variance = variance;
+    }
+
     /// sets the standard deviation field
-    pub fn set_std(&mut self, std: f64) {
+    pub fn set_std(&mut self, std: Option<f64>) {
         self.std = std;
     }
+
+    /// returns the currency or percent unit most values of the column are written with, None for
+    /// plain numbers
+    pub fn get_unit(&self) -> Option<NumberUnit> {
+        return self.unit;
+    }
+
+    /// sets the unit field
+    pub fn set_unit(&mut self, unit: Option<NumberUnit>) {
+        self.unit = unit;
+    }
+
+    /// returns equal width bins from min to max as lower edge, upper edge and number of values,
+    /// the counts are approximate when percentiles come from a sketch
+    pub fn get_histogram(&self) -> Vec<(f64, f64, u64)> {
+        return self.histogram.clone();
+    }
+
+    /// sets the histogram field
+    pub fn set_histogram(&mut self, histogram: Vec<(f64, f64, u64)>) {
+        self.histogram = histogram;
+    }
+
+    /// sets the row and null counts of the column
+    pub fn set_profile(&mut self, profile: ColumnProfile) {
+        self.profile = profile;
+    }
+}
+
+// stores summary of a column with integer values. Sum, min and max are exact, the remaining
+// statistics are calculated on float values
+#[derive(Debug, Clone)]
+pub struct IntegerColumn {
+    sum: i128,
+    min: i64,
+    max: i64,
+    statistics: NumberColumn,
+}
+
+impl IntegerColumn {
+    /// returns a new IntegerColumn object with all values set to 0
+    pub fn new() -> Self {
+        return IntegerColumn {
+            sum: 0,
+            min: 0,
+            max: 0,
+            statistics: NumberColumn::new(),
+        };
+    }
+
+    /// returns an IntegerColumn object from an existing one. Mostly used to get an IntegerColumn
+    /// object out of other encapsulation
+    pub fn build_summary(&self) -> IntegerColumn {
+        let mut integer_column_summary = IntegerColumn::new();
+        integer_column_summary.set_sum(self.get_sum());
+        integer_column_summary.set_min(self.get_min());
+        integer_column_summary.set_max(self.get_max());
+        integer_column_summary.set_statistics(self.get_statistics());
+
+        return integer_column_summary;
+    }
+
+    /// returns the exact sum of the column
+    pub fn get_sum(&self) -> i128 {
+        return self.sum;
+    }
+
+    /// returns the smallest value of the column
+    pub fn get_min(&self) -> i64 {
+        return self.min;
+    }
+
+    /// returns the largest value of the column
+    pub fn get_max(&self) -> i64 {
+        return self.max;
+    }
+
+    /// returns the exact difference between the largest and the smallest value
+    pub fn get_range(&self) -> i128 {
+        return self.max as i128 - self.min as i128;
+    }
+
+    /// returns float statistics (mean, median, standard deviation) of the column
+    pub fn get_statistics(&self) -> NumberColumn {
+        return self.statistics.build_summary();
+    }
+
+    /// returns the row and null counts of the column
+    pub fn get_profile(&self) -> ColumnProfile {
+        return self.statistics.get_profile();
+    }
+
+    /// sets the sum field
+    pub fn set_sum(&mut self, sum: i128) {
+        self.sum = sum;
+    }
+
+    /// sets the min field
+    pub fn set_min(&mut self, min: i64) {
+        self.min = min;
+    }
+
+    /// sets the max field
+    pub fn set_max(&mut self, max: i64) {
+        self.max = max;
+    }
+
+    /// sets the float statistics of the column, including its profile
+    pub fn set_statistics(&mut self, statistics: NumberColumn) {
+        self.statistics = statistics;
+    }
+}
+
+// stores summary of a column with boolean flags
+#[derive(Debug, Clone)]
+pub struct BooleanColumn {
+    true_count: u64,
+    false_count: u64,
+    profile: ColumnProfile,
+}
+
+impl BooleanColumn {
+    /// returns a new BooleanColumn object with both counts set to 0
+    pub fn new() -> Self {
+        return BooleanColumn {
+            true_count: 0,
+            false_count: 0,
+            profile: ColumnProfile::new(),
+        };
+    }
+
+    /// returns a BooleanColumn object from an existing one. Mostly used to get a BooleanColumn
+    /// object out of other encapsulation
+    pub fn build_summary(&self) -> BooleanColumn {
+        return BooleanColumn {
+            true_count: self.get_true_count(),
+            false_count: self.get_false_count(),
+            profile: self.get_profile(),
+        };
+    }
+
+    /// returns the number of true values
+    pub fn get_true_count(&self) -> u64 {
+        return self.true_count;
+    }
+
+    /// returns the number of false values
+    pub fn get_false_count(&self) -> u64 {
+        return self.false_count;
+    }
+
+    /// returns the share of true values among true and false values, 0.0 if there are none
+    pub fn get_true_ratio(&self) -> f64 {
+        let flag_count = self.true_count + self.false_count;
+        if flag_count == 0 {
+            return 0.0;
+        }
+        return self.true_count as f64 / flag_count as f64;
+    }
+
+    /// returns the row and null counts of the column
+    pub fn get_profile(&self) -> ColumnProfile {
+        return self.profile.clone();
+    }
+
+    /// sets the true_count field
+    pub fn set_true_count(&mut self, true_count: u64) {
+        self.true_count = true_count;
+    }
+
+    /// sets the false_count field
+    pub fn set_false_count(&mut self, false_count: u64) {
+        self.false_count = false_count;
+    }
+
+    /// sets the row and null counts of the column
+    pub fn set_profile(&mut self, profile: ColumnProfile) {
+        self.profile = profile;
+    }
 }
 
 // stores summary of a column with date values
+#[derive(Debug, Clone)]
 pub struct DateColumn {
     earliest: Option<NaiveDate>,
     latest: Option<NaiveDate>,
+    date_order: Option<DateOrder>,
+    // no unambiguous date decided the order and none was forced
+    date_order_assumed: bool,
+    ambiguous_count: u64,
+    profile: ColumnProfile,
 }
 
 impl DateColumn {
@@ -150,17 +964,24 @@ impl DateColumn {
         return DateColumn {
             earliest: None,
             latest: None,
+            date_order: None,
+            date_order_assumed: false,
+            ambiguous_count: 0,
+            profile: ColumnProfile::new(),
         };
     }
 
     /// returns a new DateColumn object from an existing one. Mostly used to extract DateColumn
     /// objects from other encapsulations
     pub fn build_summary(&self) -> DateColumn {
-        let mut date_column_summary = DateColumn::new();
-        date_column_summary.set_earliest(self.get_earliest().unwrap());
-        date_column_summary.set_latest(self.get_latest().unwrap());
-
-        return date_column_summary;
+        return DateColumn {
+            earliest: self.get_earliest(),
+            latest: self.get_latest(),
+            date_order: self.get_date_order(),
+            date_order_assumed: self.is_date_order_assumed(),
+            ambiguous_count: self.get_ambiguous_count(),
+            profile: self.get_profile(),
+        };
     }
 
     /// returns earliest field from the object
@@ -173,6 +994,29 @@ impl DateColumn {
         return self.latest;
     }
 
+    /// returns the order of day and month used for dates with the year last, None if the column
+    /// has no such dates
+    pub fn get_date_order(&self) -> Option<DateOrder> {
+        return self.date_order;
+    }
+
+    /// returns whether the date order is a default rather than inferred, because every date with
+    /// the year last was valid in both orders and no order was forced
+    pub fn is_date_order_assumed(&self) -> bool {
+        return self.date_order_assumed;
+    }
+
+    /// returns the number of dates with the year last that make a valid, different date in both
+    /// orders
+    pub fn get_ambiguous_count(&self) -> u64 {
+        return self.ambiguous_count;
+    }
+
+    /// returns the row and null counts of the column
+    pub fn get_profile(&self) -> ColumnProfile {
+        return self.profile.clone();
+    }
+
     /// sets earliest field of the object to Some(passed_value) where passed_value is of type
     /// chrono::NaiveDate
     pub fn set_earliest(&mut self, date: NaiveDate) {
@@ -184,15 +1028,145 @@ impl DateColumn {
     pub fn set_latest(&mut self, date: NaiveDate) {
         self.latest = Some(date);
     }
+
+    /// sets the order of day and month used for dates with the year last
+    pub fn set_date_order(&mut self, date_order: Option<DateOrder>) {
+        self.date_order = date_order;
+    }
+
+    /// sets whether the date order is a default rather than inferred
+    pub fn set_date_order_assumed(&mut self, date_order_assumed: bool) {
+        self.date_order_assumed = date_order_assumed;
+    }
+
+    /// sets the number of dates that make a valid date in both orders
+    pub fn set_ambiguous_count(&mut self, ambiguous_count: u64) {
+        self.ambiguous_count = ambiguous_count;
+    }
+
+    /// sets the row and null counts of the column
+    pub fn set_profile(&mut self, profile: ColumnProfile) {
+        self.profile = profile;
+    }
+}
+
+// stores summary of a column with timestamps. Values without a utc offset are compared as utc
+#[derive(Debug, Clone)]
+pub struct DateTimeColumn {
+    earliest: Option<DateTime<FixedOffset>>,
+    latest: Option<DateTime<FixedOffset>>,
+    hour_counts: [u64; 24],
+    offsets: Vec<FixedOffset>,
+    profile: ColumnProfile,
+}
+
+impl DateTimeColumn {
+    /// creates new DateTimeColumn object without timestamps
+    pub fn new() -> Self {
+        return DateTimeColumn {
+            earliest: None,
+            latest: None,
+            hour_counts: [0; 24],
+            offsets: vec![],
+            profile: ColumnProfile::new(),
+        };
+    }
+
+    /// returns a new DateTimeColumn object from an existing one. Mostly used to extract
+    /// DateTimeColumn objects from other encapsulations
+    pub fn build_summary(&self) -> DateTimeColumn {
+        return DateTimeColumn {
+            earliest: self.get_earliest(),
+            latest: self.get_latest(),
+            hour_counts: self.get_hour_counts(),
+            offsets: self.get_offsets(),
+            profile: self.get_profile(),
+        };
+    }
+
+    /// returns earliest timestamp of the column, with the offset it was written with
+    pub fn get_earliest(&self) -> Option<DateTime<FixedOffset>> {
+        return self.earliest;
+    }
+
+    /// returns latest timestamp of the column, with the offset it was written with
+    pub fn get_latest(&self) -> Option<DateTime<FixedOffset>> {
+        return self.latest;
+    }
+
+    /// returns the time between the earliest and the latest timestamp
+    pub fn get_span(&self) -> Option<TimeDelta> {
+        return Some(self.latest? - self.earliest?);
+    }
+
+    /// returns the number of timestamps for every hour of the day (0 to 23), in local time as
+    /// written in the values
+    pub fn get_hour_counts(&self) -> [u64; 24] {
+        return self.hour_counts;
+    }
+
+    /// returns the distinct utc offsets written in the values, from west to east
+    pub fn get_offsets(&self) -> Vec<FixedOffset> {
+        return self.offsets.clone();
+    }
+
+    /// returns the row and null counts of the column
+    pub fn get_profile(&self) -> ColumnProfile {
+        return self.profile.clone();
+    }
+
+    /// sets earliest field of the object
+    pub fn set_earliest(&mut self, timestamp: DateTime<FixedOffset>) {
+        self.earliest = Some(timestamp);
+    }
+
+    /// sets latest field of the object
+    pub fn set_latest(&mut self, timestamp: DateTime<FixedOffset>) {
+        self.latest = Some(timestamp);
+    }
+
+    /// sets the number of timestamps for every hour of the day
+    pub fn set_hour_counts(&mut self, hour_counts: [u64; 24]) {
+        self.hour_counts = hour_counts;
+    }
+
+    /// sets the distinct utc offsets written in the values
+    pub fn set_offsets(&mut self, offsets: Vec<FixedOffset>) {
+        self.offsets = offsets;
+    }
+
+    /// sets the row and null counts of the column
+    pub fn set_profile(&mut self, profile: ColumnProfile) {
+        self.profile = profile;
+    }
 }
 
 // stores summary of any column
+#[derive(Debug, Clone)]
 pub enum ColumnSummary {
     Text(TextColumn),
     Number(NumberColumn),
     Date(DateColumn),
+    DateTime(DateTimeColumn),
+    Integer(IntegerColumn),
+    Boolean(BooleanColumn),
+}
+
+impl ColumnSummary {
+    /// returns the row and null counts of the column, whatever its type
+    pub fn get_profile(&self) -> ColumnProfile {
+        return match self {
+            ColumnSummary::Text(text_column) => text_column.get_profile(),
+            ColumnSummary::Number(number_column) => number_column.get_profile(),
+            ColumnSummary::Date(date_column) => date_column.get_profile(),
+            ColumnSummary::DateTime(datetime_column) => datetime_column.get_profile(),
+            ColumnSummary::Integer(integer_column) => integer_column.get_profile(),
+            ColumnSummary::Boolean(boolean_column) => boolean_column.get_profile(),
+        };
+    }
 }
 
+#[derive(Debug, Clone)]
 pub struct PossibleDate {
     year: Option<i32>,
     month: Option<u32>,
@@ -0,0 +1,130 @@
+//! stores the error type returned by the summary pipeline.
+
+use std::fmt;
+use std::io;
+
+/// failure that stopped a summary. Every variant maps to its own process exit code, so scripts
+/// calling the binary can react to each case
+#[derive(Debug)]
+pub enum SummaryError {
+    /// the source could not be opened or read
+    Io(io::Error),
+    /// a record could not be parsed, line is None when the position is unknown
+    Csv {
+        line: Option<u64>,
+        source: csv::Error,
+    },
+    /// a record has a different number of fields than the header
+    RaggedRow {
+        line: u64,
+        expected: u64,
+        found: u64,
+    },
+    /// a column has no values to summarize
+    EmptyColumn(String),
+    /// the column names set for a source without a header do not match its number of fields
+    ColumnNameCount { expected: u64, found: u64 },
+    /// a worker thread panicked before returning its aggregates
+    ThreadJoin,
+}
+
+impl SummaryError {
+    /// returns the process exit code for this error
+    pub fn exit_code(&self) -> i32 {
+        return match self {
+            SummaryError::Io(_) => 3,
+            SummaryError::Csv { .. } => 4,
+            SummaryError::RaggedRow { .. } => 5,
+            SummaryError::EmptyColumn(_) => 6,
+            SummaryError::ThreadJoin => 7,
+            SummaryError::ColumnNameCount { .. } => 8,
+        };
+    }
+}
+
+impl fmt::Display for SummaryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            SummaryError::Io(e) => write!(f, "failed to read the file: {}", e),
+            SummaryError::Csv {
+                line: Some(line),
+                source,
+            } => match source.kind() {
+                // the position inside the csv error can be relative to a chunk, so only the
+                // reason is shown next to the line number
+                csv::ErrorKind::Utf8 { err, .. } => {
+                    write!(f, "failed to parse line {}: {}", line, err)
+                }
+                _ => write!(f, "failed to parse line {}: {}", line, source),
+            },
+            SummaryError::Csv { line: None, source } => {
+                write!(f, "failed to parse the file: {}", source)
+            }
+            SummaryError::RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {} has {} fields, but the header has {}",
+                line, found, expected
+            ),
+            SummaryError::EmptyColumn(column_name) => {
+                write!(f, "column {} has no values to summarize", column_name)
+            }
+            SummaryError::ThreadJoin => write!(f, "a worker thread stopped unexpectedly"),
+            SummaryError::ColumnNameCount { expected, found } => write!(
+                f,
+                "{} column names were given with --column-names or --header-file, but the first \
+                 record has {} fields",
+                found, expected
+            ),
+        };
+    }
+}
+
+impl std::error::Error for SummaryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return match self {
+            SummaryError::Io(e) => Some(e),
+            SummaryError::Csv { source, .. } => Some(source),
+            _ => None,
+        };
+    }
+}
+
+impl From<io::Error> for SummaryError {
+    fn from(error: io::Error) -> Self {
+        return SummaryError::Io(error);
+    }
+}
+
+impl From<csv::Error> for SummaryError {
+    /// keeps io errors and unequal record lengths reported by the csv reader as their own variants
+    fn from(error: csv::Error) -> Self {
+        if let csv::ErrorKind::UnequalLengths {
+            pos,
+            expected_len,
+            len,
+        } = error.kind()
+        {
+            return SummaryError::RaggedRow {
+                line: pos.as_ref().map_or(0, |p| p.line()),
+                expected: *expected_len,
+                found: *len,
+            };
+        }
+        if error.is_io_error() {
+            if let csv::ErrorKind::Io(e) = error.into_kind() {
+                return SummaryError::Io(e);
+            }
+            unreachable!("is_io_error guarantees an io error kind");
+        }
+
+        let line = error.position().map(|p| p.line());
+        return SummaryError::Csv {
+            line,
+            source: error,
+        };
+    }
+}
@@ -0,0 +1,189 @@
+//! writes the summary as a single self-contained html page: the report tables, sortable by
+//! clicking a header, and inline SVG histograms of number columns and of the hours of timestamp
+//! columns. Styles and script are embedded, so the page works offline.
+
+use crate::report;
+use chrono::SecondsFormat;
+use csv_summary::encapsulators::ColumnSummary;
+
+/// width and height of a histogram in pixels, without the labels
+const HISTOGRAM_WIDTH: f64 = 400.0;
+const HISTOGRAM_HEIGHT: f64 = 100.0;
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2em;color:#222}
+table{border-collapse:collapse;margin-bottom:2em;font-size:14px}
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}
+th{background:#f3f3f3;cursor:pointer;user-select:none}
+th[data-order=asc]::after{content:' \\25B2'}
+th[data-order=desc]::after{content:' \\25BC'}
+figure{display:inline-block;margin:0 2em 2em 0}
+figcaption{font-weight:bold;margin-bottom:4px}
+rect{fill:#4c78a8}
+rect:hover{fill:#f58518}
+text{font-size:11px;fill:#555}";
+
+/// sorts the rows of a table by the clicked header, numerically when both cells are numbers
+const SCRIPT: &str = "document.querySelectorAll('table.sortable th').forEach(function (th) {
+  th.addEventListener('click', function () {
+    var table = th.closest('table');
+    var body = table.tBodies[0];
+    var index = Array.prototype.indexOf.call(th.parentNode.children, th);
+    var ascending = th.dataset.order !== 'asc';
+    table.querySelectorAll('th').forEach(function (other) { delete other.dataset.order; });
+    th.dataset.order = ascending ? 'asc' : 'desc';
+    var rows = Array.prototype.slice.call(body.rows);
+    rows.sort(function (a, b) {
+      var x = a.cells[index].textContent, y = b.cells[index].textContent;
+      var nx = parseFloat(x), ny = parseFloat(y);
+      var result = !isNaN(nx) && !isNaN(ny) ? nx - ny : x.localeCompare(y);
+      return ascending ? result : -result;
+    });
+    rows.forEach(function (row) { body.appendChild(row); });
+  });
+});";
+
+/// escapes text for html element content and attribute values
+fn escape(text: &str) -> String {
+    return text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;");
+}
+
+/// returns an SVG bar chart of the counts with a tooltip per bar and the labels under the first and
+/// last bar
+fn build_histogram(bars: &[(String, u64)], first_label: &str, last_label: &str) -> String {
+    let max_count = bars
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let bar_width = HISTOGRAM_WIDTH / bars.len().max(1) as f64;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" role=\"img\">",
+        HISTOGRAM_WIDTH,
+        HISTOGRAM_HEIGHT + 16.0
+    );
+    for (index, (label, count)) in bars.iter().enumerate() {
+        let height = *count as f64 / max_count as f64 * HISTOGRAM_HEIGHT;
+        svg.push_str(&format!(
+            "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\"><title>{}: {}</title></rect>",
+            index as f64 * bar_width + 0.5,
+            HISTOGRAM_HEIGHT - height,
+            (bar_width - 1.0).max(0.5),
+            height,
+            escape(label),
+            count
+        ));
+    }
+    svg.push_str(&format!(
+        "<text x=\"0\" y=\"{}\">{}</text><text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text></svg>",
+        HISTOGRAM_HEIGHT + 12.0,
+        escape(first_label),
+        HISTOGRAM_WIDTH,
+        HISTOGRAM_HEIGHT + 12.0,
+        escape(last_label)
+    ));
+    return svg;
+}
+
+/// returns the histograms of the columns: value bins of number and integer columns and hours of
+/// the day of timestamp columns, each with the column name
+fn build_histograms(column_summaries: &[(String, ColumnSummary)]) -> Vec<(String, String)> {
+    let mut histograms: Vec<(String, String)> = vec![];
+    for (column_name, summary) in column_summaries {
+        let number_column = match summary {
+            ColumnSummary::Number(number_column) => number_column.clone(),
+            ColumnSummary::Integer(integer_column) => integer_column.get_statistics(),
+            ColumnSummary::DateTime(datetime_column) => {
+                let bars: Vec<(String, u64)> = datetime_column
+                    .get_hour_counts()
+                    .iter()
+                    .enumerate()
+                    .map(|(hour, count)| (format!("{:02}:00", hour), *count))
+                    .collect();
+                let svg = build_histogram(&bars, "00", "23");
+                histograms.push((format!("{} (hour of day)", column_name), svg));
+                continue;
+            }
+            _ => continue,
+        };
+
+        let histogram = number_column.get_histogram();
+        let (Some(first), Some(last)) = (histogram.first(), histogram.last()) else {
+            continue;
+        };
+        let bars: Vec<(String, u64)> = histogram
+            .iter()
+            .map(|(lower, upper, count)| (format!("{:.4} to {:.4}", lower, upper), *count))
+            .collect();
+        let svg = build_histogram(&bars, &format!("{:.4}", first.0), &format!("{:.4}", last.1));
+        histograms.push((column_name.clone(), svg));
+    }
+    return histograms;
+}
+
+/// prints the summary of the file at path as an html page to standard output
+pub(crate) fn display_html_stats(path: &str, column_summaries: &[(String, ColumnSummary)]) {
+    let row_count = column_summaries
+        .first()
+        .map_or(0, |(_, summary)| summary.get_profile().get_row_count());
+    let title = format!("Summary of {}", path);
+
+    println!("<!DOCTYPE html>");
+    println!("<html lang=\"en\">");
+    println!("<head>");
+    println!("<meta charset=\"utf-8\">");
+    println!("<title>{}</title>", escape(&title));
+    println!("<style>\n{}\n</style>", STYLE);
+    println!("</head>");
+    println!("<body>");
+    println!("<h1>{}</h1>", escape(&title));
+    println!(
+        "<p>{} rows, {} columns, generated {}</p>",
+        row_count,
+        column_summaries.len(),
+        chrono::Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+    );
+
+    for table in report::build_tables(column_summaries) {
+        println!("<h2>{}</h2>", escape(table.title));
+        println!("<table class=\"sortable\">");
+        let headers: Vec<String> = table
+            .headers
+            .iter()
+            .map(|header| format!("<th>{}</th>", escape(header)))
+            .collect();
+        println!("<thead><tr>{}</tr></thead>", headers.concat());
+        println!("<tbody>");
+        for row in table.rows.iter() {
+            let cells: Vec<String> = row
+                .iter()
+                .map(|cell| format!("<td>{}</td>", escape(cell)))
+                .collect();
+            println!("<tr>{}</tr>", cells.concat());
+        }
+        println!("</tbody>");
+        println!("</table>");
+    }
+
+    let histograms = build_histograms(column_summaries);
+    if !histograms.is_empty() {
+        println!("<h2>Histograms</h2>");
+        for (column_name, svg) in histograms {
+            println!(
+                "<figure><figcaption>{}</figcaption>{}</figure>",
+                escape(&column_name),
+                svg
+            );
+        }
+    }
+
+    println!("<script>\n{}\n</script>", SCRIPT);
+    println!("</body>");
+    println!("</html>");
+}
@@ -0,0 +1,115 @@
+//! opens the csv source passed by the user: a file, or standard input when the path is `-`.
+//! Gzip, zstd and bzip2 compressed sources are decompressed on the fly.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// path that stands for standard input
+pub const STDIN_PATH: &str = "-";
+
+/// compression formats recognized in a source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+impl Compression {
+    /// returns the compression matching the first bytes of a source, or None if the bytes do not
+    /// start with a known magic number
+    pub fn from_magic_bytes(bytes: &[u8]) -> Option<Compression> {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            return Some(Compression::Gzip);
+        }
+        if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            return Some(Compression::Zstd);
+        }
+        // BZh is plain text, so the block size digit and the magic of the first block (or of the
+        // end of an empty stream) have to follow it
+        if let [b'B', b'Z', b'h', b'1'..=b'9', block_magic @ ..] = bytes {
+            if block_magic.starts_with(&[0x31, 0x41, 0x59, 0x26, 0x53, 0x59])
+                || block_magic.starts_with(&[0x17, 0x72, 0x45, 0x38, 0x50, 0x90])
+            {
+                return Some(Compression::Bzip2);
+            }
+        }
+        return None;
+    }
+
+    /// returns the compression matching the extension of a path, or None for other extensions
+    pub fn from_extension(path: &str) -> Option<Compression> {
+        let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        return match extension.as_str() {
+            "gz" | "gzip" => Some(Compression::Gzip),
+            "zst" | "zstd" => Some(Compression::Zstd),
+            "bz2" | "bzip2" => Some(Compression::Bzip2),
+            _ => None,
+        };
+    }
+}
+
+/// opens the source at path, `-` opens standard input. Compression is detected from the magic
+/// bytes at the start of the source, falling back to the extension of the path, and the returned
+/// reader yields the decompressed bytes
+pub fn open(path: &str) -> io::Result<Box<dyn Read>> {
+    let reader: Box<dyn Read> = if path == STDIN_PATH {
+        Box::new(io::stdin())
+    } else {
+        Box::new(File::open(path)?)
+    };
+    let mut buffered_reader = BufReader::new(reader);
+
+    // peek at the first bytes without consuming them
+    let first_bytes = buffered_reader.fill_buf()?;
+    let compression = Compression::from_magic_bytes(first_bytes)
+        .or_else(|| Compression::from_extension(path))
+        .unwrap_or(Compression::None);
+
+    return decompress(buffered_reader, compression);
+}
+
+/// wraps the reader in a decoder for the passed compression
+pub fn decompress<R: BufRead + 'static>(
+    reader: R,
+    compression: Compression,
+) -> io::Result<Box<dyn Read>> {
+    return Ok(match compression {
+        Compression::None => Box::new(reader),
+        // multi member decoders also read files that were concatenated after compression
+        Compression::Gzip => Box::new(flate2::bufread::MultiGzDecoder::new(reader)),
+        Compression::Zstd => Box::new(zstd::Decoder::with_buffer(reader)?),
+        Compression::Bzip2 => Box::new(bzip2::bufread::MultiBzDecoder::new(reader)),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn magic_bytes_detect_bzip2_streams_only() {
+        let mut encoder = bzip2::write::BzEncoder::new(vec![], bzip2::Compression::default());
+        encoder.write_all(b"a,b\n1,2\n").unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert_eq!(
+            Compression::from_magic_bytes(&compressed),
+            Some(Compression::Bzip2)
+        );
+
+        let empty = bzip2::write::BzEncoder::new(vec![], bzip2::Compression::default())
+            .finish()
+            .unwrap();
+        assert_eq!(
+            Compression::from_magic_bytes(&empty),
+            Some(Compression::Bzip2)
+        );
+
+        // csv files whose first header starts with BZh are plain text
+        assert_eq!(Compression::from_magic_bytes(b"BZh,a\n1,2\n"), None);
+        assert_eq!(Compression::from_magic_bytes(b"BZh9,a\n1,2\n"), None);
+    }
+}
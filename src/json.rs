@@ -0,0 +1,516 @@
+//! writes the summary as one JSON document. The layout of the document is described by the
+//! structs below and versioned with SCHEMA_VERSION, which is raised on every change that removes
+//! or renames a field or changes its meaning. Adding fields does not raise it.
+//!
+//! The document is an object with
+//! - `schema_version`: version of this layout
+//! - `tool`: name and version of the program that wrote it
+//! - `generated_at`: RFC 3339 timestamp of the run
+//! - `source`: path of the summarized file, its row and column counts
+//! - `settings`: options that change the statistics. Options that can be set per column are lists
+//!   of `{column, value}` objects in command line order, `column` is null for every column
+//! - `columns`: one object per column, in header order, with `name`, `type` (text, number,
+//!   integer, date, datetime or boolean), `profile` with the counts shared by all types and
+//!   `statistics` with the values of the column type
+//!
+//! Undefined values, like the variance of a single value or the latest date of a column without
+//! dates, are null.
+
+use crate::{format_datetime, format_percentile};
+use chrono::SecondsFormat;
+use csv_summary::encapsulators::{self, ColumnSummary, ValueKind};
+use csv_summary::SummaryError;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+
+/// version of the document layout
+pub(crate) const SCHEMA_VERSION: u32 = 1;
+
+/// whole summary document
+#[derive(Serialize)]
+struct Document {
+    schema_version: u32,
+    tool: Tool,
+    generated_at: String,
+    source: Source,
+    settings: ReportSettings,
+    columns: Vec<Column>,
+}
+
+/// program that wrote the document
+#[derive(Serialize)]
+struct Tool {
+    name: &'static str,
+    version: &'static str,
+}
+
+/// summarized file
+#[derive(Serialize)]
+struct Source {
+    // path as passed on the command line, - for standard input
+    path: String,
+    // data rows, without the header
+    row_count: u64,
+    column_count: usize,
+}
+
+/// options that change the statistics of the summary
+#[derive(Serialize)]
+pub(crate) struct ReportSettings {
+    pub(crate) null_values: Vec<String>,
+    pub(crate) type_threshold: f64,
+    // population or sample
+    pub(crate) variance_kind: &'static str,
+    pub(crate) percentiles: Vec<f64>,
+    // null for exact percentiles
+    pub(crate) sketch_size: Option<usize>,
+    pub(crate) top_k: usize,
+    pub(crate) distinct_threshold: usize,
+    // seconds or milliseconds
+    pub(crate) epoch_units: Vec<ColumnSetting<&'static str>>,
+    // dmy or mdy, null detects the order from the values
+    pub(crate) date_orders: Vec<ColumnSetting<Option<&'static str>>>,
+    // chrono formats
+    pub(crate) date_formats: Vec<ColumnSetting<String>>,
+    pub(crate) number_locales: Vec<ColumnSetting<NumberLocaleSetting>>,
+}
+
+/// option set for one column, or for every column when column is None
+#[derive(Serialize)]
+pub(crate) struct ColumnSetting<T> {
+    pub(crate) column: Option<String>,
+    pub(crate) value: T,
+}
+
+impl<T> ColumnSetting<T> {
+    /// returns the settings of options parsed from the command line, with their values mapped
+    pub(crate) fn from_options<U>(
+        options: &[(Option<String>, U)],
+        map_value: impl Fn(&U) -> T,
+    ) -> Vec<ColumnSetting<T>> {
+        return options
+            .iter()
+            .map(|(column, value)| ColumnSetting {
+                column: column.clone(),
+                value: map_value(value),
+            })
+            .collect();
+    }
+}
+
+/// separators of numbers
+#[derive(Serialize)]
+pub(crate) struct NumberLocaleSetting {
+    pub(crate) decimal_separator: char,
+    pub(crate) grouping_separator: char,
+}
+
+/// summary of one column
+#[derive(Serialize)]
+struct Column {
+    name: String,
+    #[serde(rename = "type")]
+    column_type: &'static str,
+    profile: Profile,
+    statistics: Statistics,
+}
+
+/// counts shared by columns of every type
+#[derive(Serialize)]
+struct Profile {
+    row_count: u64,
+    null_count: u64,
+    null_percentage: f64,
+    // non-null values that parsed as every kind, keyed by kind name
+    type_counts: BTreeMap<&'static str, u64>,
+    // values that do not match the column type, always 0 for text columns
+    non_conforming_count: u64,
+    non_conforming_examples: Vec<String>,
+    // most frequent raw values, most frequent first, percentages are of all rows
+    top_values: Vec<TopValue>,
+}
+
+/// frequent raw value of a column
+#[derive(Serialize)]
+struct TopValue {
+    value: String,
+    count: u64,
+    percentage: f64,
+}
+
+/// values of the column type
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Statistics {
+    Text(TextStatistics),
+    Number(NumberStatistics),
+    Integer(IntegerStatistics),
+    Date(DateStatistics),
+    DateTime(DateTimeStatistics),
+    Boolean(BooleanStatistics),
+}
+
+/// statistics of a text column
+#[derive(Serialize)]
+struct TextStatistics {
+    distinct_count: u64,
+    // relative standard error of an estimated distinct_count, null when it is exact
+    distinct_count_error: Option<f64>,
+    // lengths in characters of the non-null values
+    min_length: u64,
+    max_length: u64,
+    mean_length: f64,
+    empty_count: u64,
+    leading_whitespace_count: u64,
+    trailing_whitespace_count: u64,
+    non_ascii_count: u64,
+    ascii: bool,
+    // values the patterns and the semantic type were taken from, the base of their percentages
+    profiled_count: u64,
+    pattern_count: u64,
+    untracked_pattern_count: u64,
+    top_patterns: Vec<Pattern>,
+    semantic_type: Option<SemanticType>,
+}
+
+/// frequent shape mask of a text column
+#[derive(Serialize)]
+struct Pattern {
+    pattern: String,
+    count: u64,
+    percentage: f64,
+    example: String,
+}
+
+/// semantic type of a text column with the percentage of non-null values matching it
+#[derive(Serialize)]
+struct SemanticType {
+    name: &'static str,
+    percentage: f64,
+}
+
+/// statistics of a number column
+#[derive(Serialize)]
+struct NumberStatistics {
+    count: u64,
+    sum: f64,
+    min: f64,
+    max: f64,
+    range: f64,
+    // lines in the source of the first rows holding min and max
+    min_line: u64,
+    max_line: u64,
+    mean: f64,
+    median: f64,
+    variance: Option<f64>,
+    std: Option<f64>,
+    iqr: f64,
+    // normalized rank error of sketch based percentiles, null when they are exact
+    rank_error: Option<f64>,
+    percentiles: Vec<Percentile>,
+    // currency sign or %, null for plain numbers
+    unit: Option<&'static str>,
+    histogram: Vec<Bin>,
+}
+
+/// equal width bin of the values of a number column, the last bin includes its upper edge
+#[derive(Serialize)]
+struct Bin {
+    lower: f64,
+    upper: f64,
+    count: u64,
+}
+
+/// value at a percentile, with the values at the edges of its rank error when it is approximate
+#[derive(Serialize)]
+struct Percentile {
+    name: String,
+    percentile: f64,
+    value: f64,
+    lower: Option<f64>,
+    upper: Option<f64>,
+}
+
+/// statistics of an integer column, sums and ranges are exact
+#[derive(Serialize)]
+struct IntegerStatistics {
+    count: u64,
+    sum: i128,
+    min: i64,
+    max: i64,
+    range: i128,
+    min_line: u64,
+    max_line: u64,
+    mean: f64,
+    median: f64,
+    variance: Option<f64>,
+    std: Option<f64>,
+    iqr: f64,
+    rank_error: Option<f64>,
+    percentiles: Vec<Percentile>,
+    histogram: Vec<Bin>,
+}
+
+/// statistics of a date column, dates are written as YYYY-MM-DD
+#[derive(Serialize)]
+struct DateStatistics {
+    earliest: Option<String>,
+    latest: Option<String>,
+    // dmy or mdy for dates with the year last, null when there are none
+    date_order: Option<&'static str>,
+    // true when no unambiguous date decided the order and day first was taken
+    date_order_assumed: bool,
+    // dates valid with day and month in either order
+    ambiguous_count: u64,
+}
+
+/// statistics of a timestamp column, timestamps are written in RFC 3339
+#[derive(Serialize)]
+struct DateTimeStatistics {
+    earliest: Option<String>,
+    latest: Option<String>,
+    span_seconds: Option<i64>,
+    // timestamps in every hour of the day, from 0 to 23
+    hour_counts: [u64; 24],
+    // utc offsets seen in the values, like +01:00
+    offsets: Vec<String>,
+}
+
+/// statistics of a boolean column
+#[derive(Serialize)]
+struct BooleanStatistics {
+    true_count: u64,
+    false_count: u64,
+    true_ratio: f64,
+}
+
+/// writes the summary of the file at path as a JSON document to standard output
+pub(crate) fn display_json_stats(
+    path: &str,
+    settings: ReportSettings,
+    column_summaries: &[(String, ColumnSummary)],
+) -> Result<(), SummaryError> {
+    let document = Document {
+        schema_version: SCHEMA_VERSION,
+        tool: Tool {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+        },
+        generated_at: chrono::Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        source: Source {
+            path: path.to_owned(),
+            row_count: column_summaries
+                .first()
+                .map_or(0, |(_, summary)| summary.get_profile().get_row_count()),
+            column_count: column_summaries.len(),
+        },
+        settings,
+        columns: column_summaries
+            .iter()
+            .map(|(name, summary)| build_column(name, summary))
+            .collect(),
+    };
+
+    let mut stdout = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut stdout, &document).map_err(io::Error::from)?;
+    writeln!(stdout)?;
+    return Ok(());
+}
+
+/// returns the document object of one column
+fn build_column(name: &str, summary: &ColumnSummary) -> Column {
+    let (column_type, statistics) = match summary {
+        ColumnSummary::Text(text_column) => ("text", build_text_statistics(text_column)),
+        ColumnSummary::Number(number_column) => ("number", build_number_statistics(number_column)),
+        ColumnSummary::Integer(integer_column) => {
+            ("integer", build_integer_statistics(integer_column))
+        }
+        ColumnSummary::Date(date_column) => ("date", build_date_statistics(date_column)),
+        ColumnSummary::DateTime(datetime_column) => {
+            ("datetime", build_datetime_statistics(datetime_column))
+        }
+        ColumnSummary::Boolean(boolean_column) => {
+            ("boolean", build_boolean_statistics(boolean_column))
+        }
+    };
+
+    return Column {
+        name: name.to_owned(),
+        column_type,
+        profile: build_profile(&summary.get_profile()),
+        statistics,
+    };
+}
+
+/// returns the counts shared by columns of every type
+fn build_profile(profile: &encapsulators::ColumnProfile) -> Profile {
+    return Profile {
+        row_count: profile.get_row_count(),
+        null_count: profile.get_null_count(),
+        null_percentage: profile.get_null_percentage(),
+        type_counts: ValueKind::ALL
+            .iter()
+            .map(|kind| (kind.get_name(), profile.get_type_count(*kind)))
+            .collect(),
+        non_conforming_count: profile.get_non_conforming_count(),
+        non_conforming_examples: profile.get_non_conforming_examples(),
+        top_values: profile
+            .get_top_values()
+            .into_iter()
+            .map(|(value, count, percentage)| TopValue {
+                value,
+                count,
+                percentage,
+            })
+            .collect(),
+    };
+}
+
+/// returns the statistics of a text column
+fn build_text_statistics(text_column: &encapsulators::TextColumn) -> Statistics {
+    return Statistics::Text(TextStatistics {
+        distinct_count: text_column.get_category_count(),
+        distinct_count_error: text_column.get_category_count_error(),
+        min_length: text_column.get_min_length(),
+        max_length: text_column.get_max_length(),
+        mean_length: text_column.get_mean_length(),
+        empty_count: text_column.get_empty_count(),
+        leading_whitespace_count: text_column.get_leading_whitespace_count(),
+        trailing_whitespace_count: text_column.get_trailing_whitespace_count(),
+        non_ascii_count: text_column.get_non_ascii_count(),
+        ascii: text_column.is_ascii(),
+        profiled_count: text_column.get_profiled_count(),
+        pattern_count: text_column.get_pattern_count(),
+        untracked_pattern_count: text_column.get_untracked_pattern_count(),
+        top_patterns: text_column
+            .get_top_patterns()
+            .into_iter()
+            .map(|(pattern, count, percentage, example)| Pattern {
+                pattern,
+                count,
+                percentage,
+                example,
+            })
+            .collect(),
+        semantic_type: text_column
+            .get_semantic_type()
+            .map(|(semantic_type, percentage)| SemanticType {
+                name: semantic_type.get_name(),
+                percentage,
+            }),
+    });
+}
+
+/// returns the percentiles of a number column with their bounds
+fn build_percentiles(number_column: &encapsulators::NumberColumn) -> Vec<Percentile> {
+    let bounds = number_column.get_percentile_bounds();
+    return number_column
+        .get_percentiles()
+        .into_iter()
+        .enumerate()
+        .map(|(index, (percentile, value))| Percentile {
+            name: format_percentile(percentile),
+            percentile,
+            value,
+            lower: bounds.get(index).map(|(lower, _)| *lower),
+            upper: bounds.get(index).map(|(_, upper)| *upper),
+        })
+        .collect();
+}
+
+/// returns the histogram bins of a number column
+fn build_histogram(number_column: &encapsulators::NumberColumn) -> Vec<Bin> {
+    return number_column
+        .get_histogram()
+        .into_iter()
+        .map(|(lower, upper, count)| Bin {
+            lower,
+            upper,
+            count,
+        })
+        .collect();
+}
+
+/// returns the statistics of a number column
+fn build_number_statistics(number_column: &encapsulators::NumberColumn) -> Statistics {
+    return Statistics::Number(NumberStatistics {
+        count: number_column.get_count(),
+        sum: number_column.get_sum(),
+        min: number_column.get_min(),
+        max: number_column.get_max(),
+        range: number_column.get_range(),
+        min_line: number_column.get_min_line(),
+        max_line: number_column.get_max_line(),
+        mean: number_column.get_mean(),
+        median: number_column.get_median(),
+        variance: number_column.get_variance(),
+        std: number_column.get_std(),
+        iqr: number_column.get_iqr(),
+        rank_error: number_column.get_rank_error(),
+        percentiles: build_percentiles(number_column),
+        unit: number_column.get_unit().map(|unit| unit.get_name()),
+        histogram: build_histogram(number_column),
+    });
+}
+
+/// returns the statistics of an integer column
+fn build_integer_statistics(integer_column: &encapsulators::IntegerColumn) -> Statistics {
+    let statistics = integer_column.get_statistics();
+    return Statistics::Integer(IntegerStatistics {
+        count: statistics.get_count(),
+        sum: integer_column.get_sum(),
+        min: integer_column.get_min(),
+        max: integer_column.get_max(),
+        range: integer_column.get_range(),
+        min_line: statistics.get_min_line(),
+        max_line: statistics.get_max_line(),
+        mean: statistics.get_mean(),
+        median: statistics.get_median(),
+        variance: statistics.get_variance(),
+        std: statistics.get_std(),
+        iqr: statistics.get_iqr(),
+        rank_error: statistics.get_rank_error(),
+        percentiles: build_percentiles(&statistics),
+        histogram: build_histogram(&statistics),
+    });
+}
+
+/// returns the statistics of a date column
+fn build_date_statistics(date_column: &encapsulators::DateColumn) -> Statistics {
+    return Statistics::Date(DateStatistics {
+        earliest: date_column.get_earliest().map(|date| date.to_string()),
+        latest: date_column.get_latest().map(|date| date.to_string()),
+        date_order: date_column.get_date_order().map(|order| order.get_name()),
+        date_order_assumed: date_column.is_date_order_assumed(),
+        ambiguous_count: date_column.get_ambiguous_count(),
+    });
+}
+
+/// returns the statistics of a timestamp column
+fn build_datetime_statistics(datetime_column: &encapsulators::DateTimeColumn) -> Statistics {
+    return Statistics::DateTime(DateTimeStatistics {
+        earliest: datetime_column
+            .get_earliest()
+            .map(|timestamp| format_datetime(Some(timestamp))),
+        latest: datetime_column
+            .get_latest()
+            .map(|timestamp| format_datetime(Some(timestamp))),
+        span_seconds: datetime_column.get_span().map(|span| span.num_seconds()),
+        hour_counts: datetime_column.get_hour_counts(),
+        offsets: datetime_column
+            .get_offsets()
+            .iter()
+            .map(|offset| offset.to_string())
+            .collect(),
+    });
+}
+
+/// returns the statistics of a boolean column
+fn build_boolean_statistics(boolean_column: &encapsulators::BooleanColumn) -> Statistics {
+    return Statistics::Boolean(BooleanStatistics {
+        true_count: boolean_column.get_true_count(),
+        false_count: boolean_column.get_false_count(),
+        true_ratio: boolean_column.get_true_ratio(),
+    });
+}
@@ -0,0 +1,35 @@
+//! summarizes csv files column by column. Every column is classified as text, number, integer,
+//! boolean, date or timestamp and gets aggregate statistics matching its type.
+//!
+//! ```no_run
+//! let summarizer = csv_summary::Summarizer::new();
+//! let file = csv_summary::input::open("data.csv.gz").unwrap();
+//! for (column_name, column_summary) in summarizer.summarize_reader(file).unwrap() {
+//!     if let csv_summary::encapsulators::ColumnSummary::Number(number_column) = column_summary {
+//!         println!("{} mean: {}", column_name, number_column.get_mean());
+//!     }
+//! }
+//! ```
+
+// explicit returns and `new()` constructors are the house style
+#![allow(clippy::needless_return, clippy::new_without_default)]
+
+mod aggregator;
+mod chunker;
+mod dialect;
+mod distinct;
+pub mod encapsulators;
+mod error;
+pub mod input;
+mod parsing;
+mod quantiles;
+mod settings;
+mod summarizer;
+
+pub use dialect::Dialect;
+pub use distinct::DEFAULT_DISTINCT_THRESHOLD;
+pub use error::SummaryError;
+pub use parsing::{EpochUnit, NumberLocale, DEFAULT_NULL_VALUES, DEFAULT_NUMBER_LOCALE};
+pub use quantiles::{DEFAULT_PERCENTILES, DEFAULT_SKETCH_SIZE};
+pub use settings::VarianceKind;
+pub use summarizer::Summarizer;
@@ -1,371 +1,710 @@
-pub mod encapsulators;
+// explicit returns are the house style
+#![allow(clippy::needless_return)]
 
-use chrono::NaiveDate;
+use chrono::{DateTime, FixedOffset, NaiveDate, SecondsFormat, TimeDelta};
 use clap::Parser;
-use csv;
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::sync::mpsc;
-use std::thread;
+use csv_summary::encapsulators::{self, DateOrder};
+use csv_summary::{
+    input, Dialect, EpochUnit, NumberLocale, Summarizer, SummaryError, VarianceKind,
+};
+use std::process;
+
+mod html;
+mod json;
+mod markdown;
+mod report;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
+#[command(after_help = "Exit codes:
+  0  summary printed
+  3  the file could not be opened, read or decompressed
+  4  a record could not be parsed
+  5  a record has a different number of fields than the header
+  6  a column has no values to summarize
+  7  a worker thread stopped unexpectedly
+  8  the column names do not match the number of fields")]
 struct Args {
+    /// path to the csv file, `-` reads from standard input. Gzip, zstd and bzip2 compressed files
+    /// are decompressed on the fly
     csv_path: String,
-    #[arg(long, default_value_t = false)]
+    /// layout of the printed summary: aligned tables, `column;type;stat;value` rows, a JSON
+    /// document (schema version 1), markdown tables or a self-contained html page with sortable
+    /// tables and histograms
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    format: OutputFormat,
+    /// same as `--format csv`
+    #[arg(long, default_value_t = false, conflicts_with = "format")]
     csv_format: bool,
+    /// number of worker threads, defaults to the number of cpus
+    #[arg(long, short = 'j')]
+    threads: Option<usize>,
+    /// field delimiter, `auto` picks the most consistent delimiter from the start of the file
+    #[arg(long, short = 'd', default_value = ",", value_parser = parse_delimiter)]
+    delimiter: Delimiter,
+    /// quote character
+    #[arg(long, default_value = "\"", value_parser = parse_character)]
+    quote: u8,
+    /// escape character used inside quoted fields
+    #[arg(long, value_parser = parse_character)]
+    escape: Option<u8>,
+    /// do not treat two quote characters in a quoted field as one quote
+    #[arg(long, default_value_t = false)]
+    no_double_quote: bool,
+    /// lines starting with this character are skipped
+    #[arg(long, value_parser = parse_character)]
+    comment: Option<u8>,
+    /// the first row is data, columns are named column_1..N unless names are passed
+    #[arg(long, default_value_t = false)]
+    no_header: bool,
+    /// comma separated column names for a file without a header
+    #[arg(
+        long,
+        value_delimiter = ',',
+        requires = "no_header",
+        conflicts_with = "header_file"
+    )]
+    column_names: Option<Vec<String>>,
+    /// file with column names for a file without a header, either as one header row or one name
+    /// per line
+    #[arg(long, requires = "no_header")]
+    header_file: Option<String>,
+    /// comma separated tokens treated as missing values, an empty item stands for an empty field
+    #[arg(long, value_delimiter = ',', default_value = ",NA,N/A,NULL,null,-")]
+    null_values: Vec<String>,
+    /// minimum share (0.0 to 1.0) of non-null values that must be numbers, booleans or dates for a
+    /// column to get that type, other columns are text
+    #[arg(long, default_value_t = 0.9)]
+    type_threshold: f64,
+    /// `[COLUMN=]seconds|millis`, numbers in the column (or in every column) are unix timestamps in
+    /// that unit. Can be repeated
+    #[arg(long, value_parser = parse_epoch)]
+    epoch: Vec<(Option<String>, EpochUnit)>,
+    /// `[COLUMN=]dmy|mdy|auto`, order of day and month in dates with the year last in the column
+    /// (or in every column). `auto` detects the order from the values. Can be repeated
+    #[arg(long, value_parser = parse_date_order)]
+    date_order: Vec<(Option<String>, Option<DateOrder>)>,
+    /// `[COLUMN=]FORMAT`, chrono format like `%Y%m%d` or `%d.%b.%Y` tried before the built-in date
+    /// detection in the column (or in every column without its own formats). Can be repeated
+    #[arg(long, value_parser = parse_date_format)]
+    date_format: Vec<(Option<String>, String)>,
+    /// `[COLUMN=]en|de|fr|ch|DG`, separators of numbers in the column (or in every column): en is
+    /// 1,234.56, de 1.234,56, fr 1 234,56 and ch 1'234.56, DG sets the decimal separator D and the
+    /// grouping separator G directly. Can be repeated
+    #[arg(long, value_parser = parse_number_locale)]
+    number_locale: Vec<(Option<String>, NumberLocale)>,
+    /// definition of variance and standard deviation, sample divides by n - 1
+    #[arg(long, value_enum, default_value_t = StdKind::Sample)]
+    std_kind: StdKind,
+    /// comma separated percentiles (0 to 100) reported for number columns, next to the median and
+    /// the interquartile range
+    #[arg(long, value_delimiter = ',', default_value = "1,5,25,50,75,95,99", value_parser = parse_percentile)]
+    percentiles: Vec<f64>,
+    /// estimate the median and percentiles with a KLL sketch, which keeps memory bounded on large
+    /// files at the cost of exact results
+    #[arg(long, default_value_t = false)]
+    sketch_quantiles: bool,
+    /// number of values kept by the top level of the sketch, larger sketches use more memory and
+    /// are more accurate
+    #[arg(long, default_value_t = csv_summary::DEFAULT_SKETCH_SIZE, requires = "sketch_quantiles")]
+    sketch_size: usize,
+    /// number of most frequent values reported for every column
+    #[arg(long, default_value_t = 5)]
+    top_k: usize,
+    /// number of distinct values of a column counted exactly, columns with more get a HyperLogLog
+    /// estimate of their distinct count
+    #[arg(long, default_value_t = csv_summary::DEFAULT_DISTINCT_THRESHOLD)]
+    distinct_threshold: usize,
+}
+
+/// field delimiter passed on the command line
+#[derive(Debug, Clone)]
+enum Delimiter {
+    Auto,
+    Character(u8),
+}
+
+/// layout of the printed summary passed on the command line
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+enum OutputFormat {
+    Human,
+    Csv,
+    Json,
+    Markdown,
+    Html,
+}
+
+/// definition of variance and standard deviation passed on the command line
+#[derive(Debug, Clone, clap::ValueEnum)]
+enum StdKind {
+    Population,
+    Sample,
+}
+
+/// parses a percentile between 0 and 100
+fn parse_percentile(value: &str) -> Result<f64, String> {
+    return match value.parse::<f64>() {
+        Ok(percentile) if (0.0..=100.0).contains(&percentile) => Ok(percentile),
+        _ => Err(format!("expected a number from 0 to 100, got {}", value)),
+    };
+}
+
+/// parses a single ascii character passed on the command line, `\t` and `tab` stand for a tab
+fn parse_character(value: &str) -> Result<u8, String> {
+    if value == "\\t" || value == "tab" {
+        return Ok(b'\t');
+    }
+
+    let bytes = value.as_bytes();
+    if bytes.len() != 1 || !bytes[0].is_ascii() {
+        return Err(format!("expected a single ascii character, got {}", value));
+    }
+    return Ok(bytes[0]);
 }
 
-fn parse_date_from_text(text_date: &str) -> Option<NaiveDate> {
-    let mut date_assembler = encapsulators::PossibleDate::new();
+/// parses the delimiter argument, either `auto` or a single character
+fn parse_delimiter(value: &str) -> Result<Delimiter, String> {
+    if value == "auto" {
+        return Ok(Delimiter::Auto);
+    }
+    return Ok(Delimiter::Character(parse_character(value)?));
+}
+
+/// parses the epoch argument, a unit optionally prefixed with the column name and `=`
+fn parse_epoch(value: &str) -> Result<(Option<String>, EpochUnit), String> {
+    let (column_name, unit) = match value.rsplit_once('=') {
+        Some((column_name, unit)) => (Some(column_name.to_owned()), unit),
+        None => (None, value),
+    };
+
+    let epoch_unit = match unit {
+        "seconds" | "s" => EpochUnit::Seconds,
+        "millis" | "milliseconds" | "ms" => EpochUnit::Milliseconds,
+        _ => return Err(format!("expected seconds or millis, got {}", unit)),
+    };
+    return Ok((column_name, epoch_unit));
+}
 
-    let split_date_iterator = text_date.split(|c: char| !c.is_digit(10)).into_iter();
+/// parses the date order argument, an order optionally prefixed with the column name and `=`
+fn parse_date_order(value: &str) -> Result<(Option<String>, Option<DateOrder>), String> {
+    let (column_name, order) = match value.rsplit_once('=') {
+        Some((column_name, order)) => (Some(column_name.to_owned()), order),
+        None => (None, value),
+    };
 
-    let mut loop_counter: u8 = 0;
-    // go over the split numbers and assemble date
-    for number_sequence in split_date_iterator {
-        loop_counter += 1;
-        // we should have the whole date after 3 iterations, so we can break out of this loop
-        if loop_counter == 4 {
-            break;
+    let date_order = match order {
+        "dmy" => Some(DateOrder::DayFirst),
+        "mdy" => Some(DateOrder::MonthFirst),
+        "auto" => None,
+        _ => return Err(format!("expected dmy, mdy or auto, got {}", order)),
+    };
+    return Ok((column_name, date_order));
+}
+
+/// parses the date format argument, a format optionally prefixed with the column name and `=`. A
+/// prefix with `%` is part of the format
+fn parse_date_format(value: &str) -> Result<(Option<String>, String), String> {
+    return Ok(match value.split_once('=') {
+        Some((column_name, format)) if !column_name.contains('%') => {
+            (Some(column_name.to_owned()), format.to_owned())
         }
-        match number_sequence.len() {
-            // year-like structure passed
-            4 => {
-                // if year is empty
-                if date_assembler.get_year() == None {
-                    // put current sequence as year
-                    let parsed = number_sequence.parse::<i32>();
-                    match parsed {
-                        Ok(year) => date_assembler.set_year(year),
-                        Err(_) => return None,
-                    }
+        _ => (None, value.to_owned()),
+    });
+}
+
+/// parses the number locale argument, a locale name or a decimal and a grouping separator,
+/// optionally prefixed with the column name and `=`
+fn parse_number_locale(value: &str) -> Result<(Option<String>, NumberLocale), String> {
+    let (column_name, locale) = match value.rsplit_once('=') {
+        Some((column_name, locale)) => (Some(column_name.to_owned()), locale),
+        None => (None, value),
+    };
+
+    let number_locale = match locale {
+        "en" => NumberLocale::new('.', ','),
+        "de" => NumberLocale::new(',', '.'),
+        "fr" => NumberLocale::new(',', ' '),
+        "ch" => NumberLocale::new('.', '\''),
+        _ => {
+            let separators: Vec<char> = locale.chars().collect();
+            match separators[..] {
+                [decimal, grouping] if decimal != grouping && !decimal.is_ascii_digit() => {
+                    NumberLocale::new(decimal, grouping)
                 }
-            }
-            _ => {
-                // if year is empty, the format is d(d)-(m)m-yyyy
-                if date_assembler.get_year() == None {
-                    // if year and day are empty, day was passed during first iteration
-                    if date_assembler.get_day() == None {
-                        // put current sequence as day
-                        let parsed = number_sequence.parse::<u32>();
-                        match parsed {
-                            Ok(day) => date_assembler.set_day(day),
-                            Err(_) => return None,
-                        }
-                        // if year is empty, but day filled, month was passed during second iteration
-                    } else {
-                        // put current sequence as month
-                        let parsed = number_sequence.parse::<u32>();
-                        match parsed {
-                            Ok(month) => date_assembler.set_month(month),
-                            Err(_) => return None,
-                        }
-                    }
-                } else {
-                    // if year is filled, the format is yyyy-(m)m-(d)d
-                    // if year is filled, but month is empty, month was passed during second
-                    // iteration
-                    if date_assembler.get_month() == None {
-                        // put current sequence as month
-                        let parsed = number_sequence.parse::<u32>();
-                        match parsed {
-                            Ok(month) => date_assembler.set_month(month),
-                            Err(_) => return None,
-                        }
-                    // if year and month are filled, day was passed during third iteration
-                    } else {
-                        // put current sequence as day
-                        let parsed = number_sequence.parse::<u32>();
-                        match parsed {
-                            Ok(day) => date_assembler.set_day(day),
-                            Err(_) => return None,
-                        }
-                    }
+                _ => {
+                    return Err(format!(
+                        "expected en, de, fr, ch or a decimal and a grouping separator, got {}",
+                        locale
+                    ))
                 }
             }
-        };
-    }
+        }
+    };
+    return Ok((column_name, number_locale));
+}
 
-    let year_exists = date_assembler.get_year() != None;
-    let month_exists = date_assembler.get_month() != None;
-    let day_exists = date_assembler.get_day() != None;
+/// reads column names from a header file parsed with the dialect of the data file. A file with a
+/// single header row gives one name per field, otherwise the first field of every row is a name
+fn read_header_file(path: &str, dialect: &Dialect) -> Result<Vec<String>, SummaryError> {
+    let mut reader = dialect.reader_builder().from_reader(input::open(path)?);
 
-    if year_exists && month_exists && day_exists {
-        let constructed_date = NaiveDate::from_ymd_opt(
-            date_assembler.get_year().unwrap(),
-            date_assembler.get_month().unwrap(),
-            date_assembler.get_day().unwrap(),
-        );
-        return constructed_date;
-    } else {
-        return None;
+    let mut rows: Vec<csv::StringRecord> = vec![];
+    for row in reader.records() {
+        rows.push(row?);
+    }
+
+    if rows.len() == 1 {
+        return Ok(rows[0].iter().map(|x| x.to_owned()).collect());
     }
+    return Ok(rows
+        .iter()
+        .filter_map(|row| row.get(0))
+        .map(|x| x.to_owned())
+        .collect());
 }
 
-/// reads a csv file, and returns a file reader and vector with all headers
-fn load_file(path: &str) -> Result<(csv::Reader<File>, Vec<String>), csv::Error> {
-    let mut reader = csv::Reader::from_path(path)?;
-    let header = reader.headers()?; // get headers
+/// formats an optional date, missing dates are shown as an empty string
+fn format_date(date: Option<NaiveDate>) -> String {
+    return date.map_or(String::new(), |d| d.to_string());
+}
 
-    // makes a vec of owned strings
-    let string_headers: Vec<String> = header.iter().map(|x| x.to_owned()).collect();
+/// formats a statistic with 4 decimal places, statistics that are not defined for the column are
+/// shown as undefined
+fn format_statistic(value: Option<f64>) -> String {
+    return value.map_or("undefined".to_owned(), |v| format!("{:.4}", v));
+}
 
-    return Ok((reader, string_headers.to_owned()));
+/// formats a statistic with full precision for the semicolon separated output
+fn format_csv_statistic(value: Option<f64>) -> String {
+    return value.map_or("undefined".to_owned(), |v| v.to_string());
 }
 
-/// extracts median value from hash map with string representation of float values and the number of
-/// their occurences
-fn get_median_from_hashmap(mut hashmap: HashMap<String, u16>) -> f64 {
-    // parse all keys into float values
-    let mut keys: Vec<f64> = hashmap
-        .keys()
-        .map(|key| key.parse::<f64>().unwrap())
-        .collect();
-
-    // sort the keys
-    let count_distinct = keys.len();
-    keys.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-    // set up the algorithm for finding the mean
-    let mut left: usize = 0;
-    let mut right: usize = count_distinct - 1;
-    let mut left_value = keys[left];
-    let mut right_value = keys[right];
-
-    // left < right means this is before the half way, where we compare the same values or compare
-    // the same stuff, or the same stuff we already compared
-    while left < right {
-        // whether there are any occurences of this value left in the hash map
-        if hashmap[&left_value.to_string()] > 0 {
-            // if yes, subtract 1 from the occurences count for this value
-            hashmap.insert(left_value.to_string(), hashmap[&left_value.to_string()] - 1);
-        } else {
-            // if no occurences of this value are left, move to a higher value as we reproach the
-            // center from left here, (and swap the left value to match the new left index
-            left += 1;
-            left_value = keys[left];
-        }
+/// formats the rank error of percentiles as a share of all values, exact percentiles have none
+fn format_rank_error(rank_error: Option<f64>) -> String {
+    return rank_error.map_or("exact".to_owned(), |e| format!("±{:.2}%", e * 100.0));
+}
 
-        // whether there are any occurences of this value left in the hash map
-        if hashmap[&right_value.to_string()] > 0 {
-            // if yes, subtract 1 from the occurences count for this value
-            hashmap.insert(
-                right_value.to_string(),
-                hashmap[&right_value.to_string()] - 1,
-            );
-        } else {
-            // if no occurences of this value are left, move to a lower value as we approach the
-            // center from right here, (and swap the value to match the new right index)
-            right -= 1;
-            right_value = keys[right];
+/// formats the most frequent shape masks of a text column with their counts, share of all rows and
+/// an example value. Control characters in masks are escaped so they do not break table rows
+fn format_top_patterns(text_column: &encapsulators::TextColumn) -> String {
+    return text_column
+        .get_top_patterns()
+        .iter()
+        .map(|(pattern, count, percentage, example)| {
+            format!(
+                "{} ({}, {:.2}%, e.g. {:?})",
+                pattern.escape_debug(),
+                count,
+                percentage,
+                example
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+}
+
+/// formats the semantic type of a text column with the share of values matching it
+fn format_semantic_type(text_column: &encapsulators::TextColumn) -> String {
+    return match text_column.get_semantic_type() {
+        Some((semantic_type, percentage)) => {
+            format!("{} ({:.2}%)", semantic_type.get_name(), percentage)
         }
-    }
+        None => "-".to_owned(),
+    };
+}
 
-    let median: f64;
+/// formats the distinct value count of a text column, with its standard error if it is estimated
+fn format_category_count(text_column: &encapsulators::TextColumn) -> String {
+    return match text_column.get_category_count_error() {
+        Some(error) => format!(
+            "~{} (±{:.2}%)",
+            text_column.get_category_count(),
+            error * 100.0
+        ),
+        None => text_column.get_category_count().to_string(),
+    };
+}
 
-    if left == right {
-        median = left_value;
-    } else {
-        // if left and right are different values (even number count, and lack of one center value),
-        // take the mean of those
-        median = (left_value + right_value) / 2.0;
-    }
+/// formats the most frequent values of a column with their counts and share of all rows
+fn format_top_values(profile: &encapsulators::ColumnProfile) -> String {
+    return profile
+        .get_top_values()
+        .iter()
+        .map(|(value, count, percentage)| format!("{:?} ({}, {:.2}%)", value, count, percentage))
+        .collect::<Vec<String>>()
+        .join(", ");
+}
 
-    return median;
+/// formats a percentile as its name, like p5 or p99.9
+fn format_percentile(percentile: f64) -> String {
+    return format!("p{}", percentile);
 }
 
-/// creates a thread for each column in the file (based on headers vector), then each thread iterates over the column values
-/// and creates aggregate statistics. Returns two hash maps: one for handles for all threads, and
-/// one with senders that pass the column values to the threads. Both hash maps are searchable using
-/// headers (column names)
-fn get_hashmaps(
-    headers: &Vec<String>,
-) -> (
-    HashMap<&String, thread::JoinHandle<encapsulators::ColumnSummary>>,
-    HashMap<&String, mpsc::Sender<encapsulators::ColumnType>>,
-) {
-    // create empty hash maps for handles and senders
-    let mut handles_map: HashMap<&String, thread::JoinHandle<encapsulators::ColumnSummary>> =
-        HashMap::new();
-    let mut sender_map: HashMap<&String, mpsc::Sender<encapsulators::ColumnType>> = HashMap::new();
-
-    // handle each column
-    for header in headers {
-        // get sender and receiver that handles ColumnType object
-        let (tx, rx) = mpsc::channel::<encapsulators::ColumnType>();
-
-        // create a thread for a given column
-        let handle = thread::spawn(move || {
-            // assumes the column has text values, used later to return the right thing
-            let mut text_column: bool = true;
-            let mut date_column: bool = false;
-
-            // create empty hash map and category count for text values
-            let mut categories: HashSet<String> = HashSet::new();
-            let mut category_count: u16 = 0;
-
-            // set up agg variables for numerical column
-            let mut sum: f64 = 0.0;
-
-            // variables for one-pass standard deviation calculation
-            let mut m: f64 = 0.0;
-            let mut s: f64 = 0.0;
-            let mut row_counter: u64 = 0;
-
-            // hash map that will store string representation of float values with the counter to
-            // calculate the median later
-            let mut mode_map: HashMap<String, u16> = HashMap::new();
-
-            // struct storing the earliest and latest date from file
-            let mut date_aggregate = encapsulators::DateColumn::new();
-
-            // wait for messages from row splitting part and handle values when they arrive
-            for message in rx {
-                row_counter += 1;
-
-                // match message based on the value type
-                match message {
-                    encapsulators::ColumnType::Float(number_value) => {
-                        // handle int/float values: add to sum, put the value in mode hash map,
-                        // handle calculating one pass standard deviation
-                        text_column = false;
-
-                        sum = sum + number_value;
-                        mode_map.entry(number_value.to_string()).or_insert(0);
-                        mode_map.insert(
-                            number_value.to_string(),
-                            mode_map[&number_value.to_string()] + 1,
-                        );
-
-                        // std calculation
-                        let old_m = m;
-                        m = m + (number_value - m) / row_counter as f64;
-                        s = s + (number_value - m) * (number_value - old_m)
-                    }
-                    encapsulators::ColumnType::Date(date_value) => {
-                        // TODO: something is wrong here with how earliest and latest is passed to
-                        // date_aggregate
-
-                        // mark this as a date column
-                        date_column = true;
-                        text_column = false;
-
-                        // swap earliest if new earliest date found
-                        let current_earliest = date_aggregate.get_earliest();
-                        match current_earliest {
-                            Some(date) => {
-                                if date_value < date {
-                                    date_aggregate.set_earliest(date_value);
-                                }
-                            }
-                            None => date_aggregate.set_earliest(date_value),
-                        }
-
-                        // swap latest if new latest date found
-                        let current_latest = date_aggregate.get_latest();
-                        match current_latest {
-                            Some(date) => {
-                                if date_value > date {
-                                    date_aggregate.set_latest(date_value)
-                                }
-                            }
-                            None => date_aggregate.set_latest(date_value),
-                        }
-                    }
-                    encapsulators::ColumnType::Text(text_value) => {
-                        // handle text values: add category to counter and hash map if they are not
-                        // already there
-                        if !categories.contains(&text_value) {
-                            categories.insert(text_value);
-                            category_count += 1;
-                        }
-                    }
-                    // FileEnd is passed only on EOF to exit the loop
-                    encapsulators::ColumnType::FileEnd => break,
-                }
-            }
-            // decrease row_counter by 1 because FileEnd value does not represent an actual file
-            // row
-            row_counter -= 1;
-
-            // return ColumnSummary value based on handles column type
-            if text_column == true {
-                let mut text_column_summary = encapsulators::TextColumn::new();
-                text_column_summary.set_categories(categories);
-                text_column_summary.set_category_count(category_count);
-
-                return encapsulators::ColumnSummary::Text(text_column_summary);
-            } else if date_column == true {
-                let date_column_summary = date_aggregate.build_summary();
-                return encapsulators::ColumnSummary::Date(date_column_summary);
-            } else {
-                // calculate summary statistics
-                let mean = sum / row_counter as f64;
-                let std = s / (row_counter - 1) as f64;
-                let median = get_median_from_hashmap(mode_map);
-
-                let mut number_column_summary = encapsulators::NumberColumn::new();
-                number_column_summary.set_sum(sum);
-                number_column_summary.set_mean(mean);
-                number_column_summary.set_median(median);
-                number_column_summary.set_std(std);
-                return encapsulators::ColumnSummary::Number(number_column_summary);
-            }
-        });
-        // put thread's handle and sender in hash maps
-        handles_map.insert(header, handle);
-        sender_map.insert(&header, tx);
+/// formats the order of day and month of a date column, columns without dates with the year last
+/// are shown as an empty string and assumed orders are marked as such
+fn format_date_order(date_column: &encapsulators::DateColumn) -> String {
+    let date_order = date_column
+        .get_date_order()
+        .map_or(String::new(), |o| o.get_name().to_owned());
+    if date_column.is_date_order_assumed() {
+        return format!("{} (assumed)", date_order);
     }
 
-    return (handles_map, sender_map);
+    return date_order;
 }
 
-/// displays the summary statistics for both text and number columns given vectors of tuples with
-/// column name and aggregated values. Numbers have precision of 4 decimal spaces
+/// formats an optional timestamp in RFC 3339, missing timestamps are shown as an empty string
+fn format_datetime(timestamp: Option<DateTime<FixedOffset>>) -> String {
+    return timestamp.map_or(String::new(), |t| {
+        t.to_rfc3339_opts(SecondsFormat::AutoSi, false)
+    });
+}
+
+/// formats an optional duration as days and hh:mm:ss, missing durations are shown as an empty
+/// string
+fn format_duration(duration: Option<TimeDelta>) -> String {
+    return duration.map_or(String::new(), |d| {
+        let seconds = d.num_seconds();
+        format!(
+            "{}d {:02}:{:02}:{:02}",
+            seconds / 86400,
+            seconds % 86400 / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    });
+}
+
+/// formats utc offsets as a comma separated list
+fn format_offsets(offsets: &[FixedOffset]) -> String {
+    return offsets
+        .iter()
+        .map(|offset| offset.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+}
+
+/// displays the summary statistics for all column types given vectors of tuples with column name
+/// and aggregated values. Numbers have precision of 4 decimal spaces
 fn display_stats(
     text_summary: Vec<(String, encapsulators::TextColumn)>,
     number_summary: Vec<(String, encapsulators::NumberColumn)>,
     date_summary: Vec<(String, encapsulators::DateColumn)>,
+    datetime_summary: Vec<(String, encapsulators::DateTimeColumn)>,
+    integer_summary: Vec<(String, encapsulators::IntegerColumn)>,
+    boolean_summary: Vec<(String, encapsulators::BooleanColumn)>,
 ) {
     println!("Text columns\n");
-    println!("column               class count          classes");
+    println!("column               nulls                null %               class count          semantic type        top values");
+    for (column_name, column_stats) in &text_summary {
+        let profile = column_stats.get_profile();
+        println!(
+            "{:<20} {:<20} {:<20.2} {:<20} {:<20} {}",
+            column_name,
+            profile.get_null_count(),
+            profile.get_null_percentage(),
+            format_category_count(column_stats),
+            format_semantic_type(column_stats),
+            format_top_values(&profile)
+        )
+    }
+
+    println!("\nText lengths\n");
+    println!("column              min length          max length          mean length         empty               leading space       trailing space      ascii");
+    for (column_name, column_stats) in &text_summary {
+        println!(
+            "{:<20}{:<20}{:<20}{:<20.2}{:<20}{:<20}{:<20}{}",
+            column_name,
+            column_stats.get_min_length(),
+            column_stats.get_max_length(),
+            column_stats.get_mean_length(),
+            column_stats.get_empty_count(),
+            column_stats.get_leading_whitespace_count(),
+            column_stats.get_trailing_whitespace_count(),
+            column_stats.is_ascii()
+        )
+    }
+
+    println!("\nText patterns\n");
+    println!("column              patterns            untracked           top patterns");
     for (column_name, column_stats) in text_summary {
-        let categories = &column_stats.get_categories();
-        let vec_categories: Vec<&String> = categories.iter().collect();
-        let count = column_stats.get_category_count();
-        if count > 10 {
-            println!("{:<20} {:<20} (a lot)", column_name, count)
-        } else {
-            println!(
-                "{:<20} {:<20} ({:<20?})",
-                column_name, count, vec_categories
-            )
-        }
+        println!(
+            "{:<20}{:<20}{:<20}{}",
+            column_name,
+            column_stats.get_pattern_count(),
+            column_stats.get_untracked_pattern_count(),
+            format_top_patterns(&column_stats)
+        )
     }
 
+    // profiles of typed columns, used to list the values that do not match the column type
+    let mut date_profiles: Vec<(String, encapsulators::ColumnProfile)> = vec![];
+    let mut datetime_profiles: Vec<(String, encapsulators::ColumnProfile)> = vec![];
+    let mut number_profiles: Vec<(String, encapsulators::ColumnProfile)> = vec![];
+    let mut integer_profiles: Vec<(String, encapsulators::ColumnProfile)> = vec![];
+    // statistics of number and integer columns, used to list their percentiles
+    let mut percentile_rows: Vec<(String, encapsulators::NumberColumn)> = vec![];
+    let mut boolean_profiles: Vec<(String, encapsulators::ColumnProfile)> = vec![];
+
     println!("\nDate columns\n");
-    println!("column              earliest            latest");
+    println!("column              nulls               null %              earliest            latest              order               ambiguous");
     for (column_name, column_stats) in date_summary {
+        let profile = column_stats.get_profile();
         println!(
-            "{:<20}{:<20}{:<20}",
+            "{:<20}{:<20}{:<20.2}{:<20}{:<20}{:<20}{:<20}",
             column_name,
-            format!("{:<20}", column_stats.get_earliest().unwrap()),
-            format!("{:<20}", column_stats.get_latest().unwrap())
-        )
+            profile.get_null_count(),
+            profile.get_null_percentage(),
+            format_date(column_stats.get_earliest()),
+            format_date(column_stats.get_latest()),
+            format_date_order(&column_stats),
+            column_stats.get_ambiguous_count()
+        );
+        date_profiles.push((column_name, profile));
+    }
+
+    println!("\nDatetime columns\n");
+    println!("column              nulls               null %              earliest                      latest                        span                offsets");
+    let mut hour_counts: Vec<(String, [u64; 24])> = vec![];
+    for (column_name, column_stats) in datetime_summary {
+        let profile = column_stats.get_profile();
+        println!(
+            "{:<20}{:<20}{:<20.2}{:<30}{:<30}{:<20}{}",
+            column_name,
+            profile.get_null_count(),
+            profile.get_null_percentage(),
+            format_datetime(column_stats.get_earliest()),
+            format_datetime(column_stats.get_latest()),
+            format_duration(column_stats.get_span()),
+            format_offsets(&column_stats.get_offsets())
+        );
+        hour_counts.push((column_name.clone(), column_stats.get_hour_counts()));
+        datetime_profiles.push((column_name, profile));
+    }
+
+    println!("\nDatetime hours\n");
+    println!("column              values per hour of day (00 to 23)");
+    for (column_name, counts) in hour_counts {
+        println!("{:<20}{:?}", column_name, counts);
     }
 
     println!("\nNumber columns\n");
-    println!("column              sum                 mean                median              std");
+    println!("column              nulls               null %              count               sum                 min                 max                 range               min line            max line            mean                median              variance            std                 unit");
     for (column_name, column_stats) in number_summary {
+        let profile = column_stats.get_profile();
         println!(
-            "{:<20}{:<20.4}{:<20.4}{:<20.4}{:<20.4}",
+            "{:<20}{:<20}{:<20.2}{:<20}{:<20.4}{:<20.4}{:<20.4}{:<20.4}{:<20}{:<20}{:<20.4}{:<20.4}{:<20}{:<20}{}",
             column_name,
+            profile.get_null_count(),
+            profile.get_null_percentage(),
+            column_stats.get_count(),
             column_stats.get_sum(),
+            column_stats.get_min(),
+            column_stats.get_max(),
+            column_stats.get_range(),
+            column_stats.get_min_line(),
+            column_stats.get_max_line(),
             column_stats.get_mean(),
             column_stats.get_median(),
-            column_stats.get_std()
-        )
+            format_statistic(column_stats.get_variance()),
+            format_statistic(column_stats.get_std()),
+            column_stats.get_unit().map_or("", |unit| unit.get_name())
+        );
+        percentile_rows.push((column_name.clone(), column_stats));
+        number_profiles.push((column_name, profile));
+    }
+
+    println!("\nInteger columns\n");
+    println!("column              nulls               null %              count               sum                 min                 max                 range               min line            max line            mean                median              variance            std");
+    for (column_name, column_stats) in integer_summary {
+        let profile = column_stats.get_profile();
+        let statistics = column_stats.get_statistics();
+        println!(
+            "{:<20}{:<20}{:<20.2}{:<20}{:<20}{:<20}{:<20}{:<20}{:<20}{:<20}{:<20.4}{:<20.4}{:<20}{:<20}",
+            column_name,
+            profile.get_null_count(),
+            profile.get_null_percentage(),
+            statistics.get_count(),
+            column_stats.get_sum(),
+            column_stats.get_min(),
+            column_stats.get_max(),
+            column_stats.get_range(),
+            statistics.get_min_line(),
+            statistics.get_max_line(),
+            statistics.get_mean(),
+            statistics.get_median(),
+            format_statistic(statistics.get_variance()),
+            format_statistic(statistics.get_std())
+        );
+        percentile_rows.push((column_name.clone(), statistics));
+        integer_profiles.push((column_name, profile));
+    }
+
+    println!("\nPercentiles\n");
+    // every column has the same percentiles, the header is taken from the first one
+    let mut percentile_header =
+        String::from("column              rank error          iqr                 ");
+    if let Some((_, column_stats)) = percentile_rows.first() {
+        for (percentile, _) in column_stats.get_percentiles() {
+            percentile_header.push_str(&format!("{:<20}", format_percentile(percentile)));
+        }
+    }
+    println!("{}", percentile_header.trim_end());
+    for (column_name, column_stats) in percentile_rows {
+        let mut row = format!(
+            "{:<20}{:<20}{:<20.4}",
+            column_name,
+            format_rank_error(column_stats.get_rank_error()),
+            column_stats.get_iqr()
+        );
+        for (_, value) in column_stats.get_percentiles() {
+            row.push_str(&format!("{:<20.4}", value));
+        }
+        println!("{}", row);
+    }
+
+    println!("\nBoolean columns\n");
+    println!("column              nulls               null %              true                false               true ratio");
+    for (column_name, column_stats) in boolean_summary {
+        let profile = column_stats.get_profile();
+        println!(
+            "{:<20}{:<20}{:<20.2}{:<20}{:<20}{:<20.4}",
+            column_name,
+            profile.get_null_count(),
+            profile.get_null_percentage(),
+            column_stats.get_true_count(),
+            column_stats.get_false_count(),
+            column_stats.get_true_ratio()
+        );
+        boolean_profiles.push((column_name, profile));
+    }
+
+    println!("\nTop values\n");
+    println!("column              top values");
+    let typed_profiles = number_profiles
+        .iter()
+        .chain(integer_profiles.iter())
+        .chain(boolean_profiles.iter())
+        .chain(date_profiles.iter())
+        .chain(datetime_profiles.iter());
+    for (column_name, profile) in typed_profiles {
+        println!("{:<20}{}", column_name, format_top_values(profile));
+    }
+
+    println!("\nMixed columns\n");
+    println!("column              non-conforming      examples");
+    let mixed_profiles = number_profiles
+        .into_iter()
+        .chain(integer_profiles)
+        .chain(boolean_profiles)
+        .chain(date_profiles)
+        .chain(datetime_profiles);
+    for (column_name, profile) in mixed_profiles {
+        if profile.get_non_conforming_count() > 0 {
+            println!(
+                "{:<20}{:<20}{:?}",
+                column_name,
+                profile.get_non_conforming_count(),
+                profile.get_non_conforming_examples()
+            )
+        }
+    }
+}
+
+/// displays the row and null counts of one column in the semicolon separated structure
+fn display_csv_profile(
+    column_name: &str,
+    column_type: &str,
+    profile: &encapsulators::ColumnProfile,
+) {
+    println!(
+        "{};{};row_count;{}",
+        column_name,
+        column_type,
+        profile.get_row_count()
+    );
+    println!(
+        "{};{};null_count;{}",
+        column_name,
+        column_type,
+        profile.get_null_count()
+    );
+    println!(
+        "{};{};null_percentage;{}",
+        column_name,
+        column_type,
+        profile.get_null_percentage()
+    );
+    for kind in encapsulators::ValueKind::ALL {
+        println!(
+            "{};{};{}_count;{}",
+            column_name,
+            column_type,
+            kind.get_name(),
+            profile.get_type_count(kind)
+        );
+    }
+    for (rank, (value, count, percentage)) in profile.get_top_values().iter().enumerate() {
+        println!(
+            "{};{};top_{}_value;{:?}",
+            column_name,
+            column_type,
+            rank + 1,
+            value
+        );
+        println!(
+            "{};{};top_{}_count;{}",
+            column_name,
+            column_type,
+            rank + 1,
+            count
+        );
+        println!(
+            "{};{};top_{}_percentage;{}",
+            column_name,
+            column_type,
+            rank + 1,
+            percentage
+        );
+    }
+    println!(
+        "{};{};non_conforming_count;{}",
+        column_name,
+        column_type,
+        profile.get_non_conforming_count()
+    );
+    println!(
+        "{};{};non_conforming_examples;{:?}",
+        column_name,
+        column_type,
+        profile.get_non_conforming_examples()
+    );
+}
+
+/// displays the interquartile range and percentiles of one number column in the semicolon
+/// separated structure
+fn display_csv_percentiles(
+    column_name: &str,
+    column_type: &str,
+    number_column: &encapsulators::NumberColumn,
+) {
+    println!(
+        "{};{};iqr;{}",
+        column_name,
+        column_type,
+        number_column.get_iqr()
+    );
+    if let Some(rank_error) = number_column.get_rank_error() {
+        println!("{};{};rank_error;{}", column_name, column_type, rank_error);
+    }
+    let bounds = number_column.get_percentile_bounds();
+    for (index, (percentile, value)) in number_column.get_percentiles().into_iter().enumerate() {
+        let name = format_percentile(percentile);
+        println!("{};{};{};{}", column_name, column_type, name, value);
+        if let Some((lower, upper)) = bounds.get(index) {
+            println!("{};{};{}_lower;{}", column_name, column_type, name, lower);
+            println!("{};{};{}_upper;{}", column_name, column_type, name, upper);
+        }
     }
 }
 
@@ -376,138 +715,440 @@ fn display_csv_stats(
     text_summary: Vec<(String, encapsulators::TextColumn)>,
     number_summary: Vec<(String, encapsulators::NumberColumn)>,
     date_summary: Vec<(String, encapsulators::DateColumn)>,
+    datetime_summary: Vec<(String, encapsulators::DateTimeColumn)>,
+    integer_summary: Vec<(String, encapsulators::IntegerColumn)>,
+    boolean_summary: Vec<(String, encapsulators::BooleanColumn)>,
 ) {
     println!("column_name;column_type;agg_stat;value");
 
     for (column_name, text_column) in text_summary {
+        display_csv_profile(&column_name, "text", &text_column.get_profile());
         println!(
             "{};text;category_count;{}",
             column_name,
             text_column.get_category_count()
         );
-        if text_column.get_categories().len() > 10 {
+        if let Some(error) = text_column.get_category_count_error() {
+            println!("{};text;category_count_error;{}", column_name, error);
+        }
+        // larger columns list their most frequent values in the top_N rows only
+        if text_column.get_category_count() <= 10 {
+            let mut categories: Vec<String> = text_column.get_categories().into_iter().collect();
+            categories.sort();
+            println!("{};text;categories;{:?}", column_name, categories);
+        }
+        println!(
+            "{};text;min_length;{}",
+            column_name,
+            text_column.get_min_length()
+        );
+        println!(
+            "{};text;max_length;{}",
+            column_name,
+            text_column.get_max_length()
+        );
+        println!(
+            "{};text;mean_length;{}",
+            column_name,
+            text_column.get_mean_length()
+        );
+        println!(
+            "{};text;empty_count;{}",
+            column_name,
+            text_column.get_empty_count()
+        );
+        println!(
+            "{};text;leading_whitespace_count;{}",
+            column_name,
+            text_column.get_leading_whitespace_count()
+        );
+        println!(
+            "{};text;trailing_whitespace_count;{}",
+            column_name,
+            text_column.get_trailing_whitespace_count()
+        );
+        println!(
+            "{};text;non_ascii_count;{}",
+            column_name,
+            text_column.get_non_ascii_count()
+        );
+        println!("{};text;ascii;{}", column_name, text_column.is_ascii());
+        if let Some((semantic_type, percentage)) = text_column.get_semantic_type() {
             println!(
-                "{};text;categories;{:?}",
+                "{};text;semantic_type;{}",
                 column_name,
-                text_column.get_categories()
+                semantic_type.get_name()
+            );
+            println!(
+                "{};text;semantic_type_percentage;{}",
+                column_name, percentage
+            );
+        }
+        println!(
+            "{};text;profiled_count;{}",
+            column_name,
+            text_column.get_profiled_count()
+        );
+        println!(
+            "{};text;pattern_count;{}",
+            column_name,
+            text_column.get_pattern_count()
+        );
+        println!(
+            "{};text;untracked_pattern_count;{}",
+            column_name,
+            text_column.get_untracked_pattern_count()
+        );
+        for (rank, (pattern, count, percentage, example)) in
+            text_column.get_top_patterns().iter().enumerate()
+        {
+            println!(
+                "{};text;top_{}_pattern;{:?}",
+                column_name,
+                rank + 1,
+                pattern
+            );
+            println!(
+                "{};text;top_{}_pattern_count;{}",
+                column_name,
+                rank + 1,
+                count
+            );
+            println!(
+                "{};text;top_{}_pattern_percentage;{}",
+                column_name,
+                rank + 1,
+                percentage
+            );
+            println!(
+                "{};text;top_{}_pattern_example;{:?}",
+                column_name,
+                rank + 1,
+                example
             );
-        } else {
-            println!("{};text;categories;(a lot)", column_name)
         }
     }
 
     for (column_name, number_column) in number_summary {
+        display_csv_profile(&column_name, "number", &number_column.get_profile());
+        println!("{};number;count;{}", column_name, number_column.get_count());
         println!("{};number;sum;{}", column_name, number_column.get_sum());
+        println!("{};number;min;{}", column_name, number_column.get_min());
+        println!("{};number;max;{}", column_name, number_column.get_max());
+        println!("{};number;range;{}", column_name, number_column.get_range());
+        if let Some(unit) = number_column.get_unit() {
+            println!("{};number;unit;{}", column_name, unit.get_name());
+        }
+        println!(
+            "{};number;min_line;{}",
+            column_name,
+            number_column.get_min_line()
+        );
+        println!(
+            "{};number;max_line;{}",
+            column_name,
+            number_column.get_max_line()
+        );
         println!("{};number;mean;{}", column_name, number_column.get_mean());
         println!(
             "{};number;median;{}",
             column_name,
             number_column.get_median()
         );
-        println!("{};number;std;{}", column_name, number_column.get_std());
+        display_csv_percentiles(&column_name, "number", &number_column);
+        println!(
+            "{};number;variance;{}",
+            column_name,
+            format_csv_statistic(number_column.get_variance())
+        );
+        println!(
+            "{};number;std;{}",
+            column_name,
+            format_csv_statistic(number_column.get_std())
+        );
+    }
+
+    for (column_name, integer_column) in integer_summary {
+        let statistics = integer_column.get_statistics();
+        display_csv_profile(&column_name, "integer", &integer_column.get_profile());
+        println!("{};integer;count;{}", column_name, statistics.get_count());
+        println!("{};integer;sum;{}", column_name, integer_column.get_sum());
+        println!("{};integer;min;{}", column_name, integer_column.get_min());
+        println!("{};integer;max;{}", column_name, integer_column.get_max());
+        println!(
+            "{};integer;range;{}",
+            column_name,
+            integer_column.get_range()
+        );
+        println!(
+            "{};integer;min_line;{}",
+            column_name,
+            statistics.get_min_line()
+        );
+        println!(
+            "{};integer;max_line;{}",
+            column_name,
+            statistics.get_max_line()
+        );
+        println!("{};integer;mean;{}", column_name, statistics.get_mean());
+        println!("{};integer;median;{}", column_name, statistics.get_median());
+        display_csv_percentiles(&column_name, "integer", &statistics);
+        println!(
+            "{};integer;variance;{}",
+            column_name,
+            format_csv_statistic(statistics.get_variance())
+        );
+        println!(
+            "{};integer;std;{}",
+            column_name,
+            format_csv_statistic(statistics.get_std())
+        );
+    }
+
+    for (column_name, boolean_column) in boolean_summary {
+        display_csv_profile(&column_name, "boolean", &boolean_column.get_profile());
+        println!(
+            "{};boolean;true_count;{}",
+            column_name,
+            boolean_column.get_true_count()
+        );
+        println!(
+            "{};boolean;false_count;{}",
+            column_name,
+            boolean_column.get_false_count()
+        );
+        println!(
+            "{};boolean;true_ratio;{}",
+            column_name,
+            boolean_column.get_true_ratio()
+        );
     }
 
     for (column_name, date_column) in date_summary {
+        display_csv_profile(&column_name, "date", &date_column.get_profile());
         println!(
             "{};date;earliest;{}",
             column_name,
-            date_column.get_earliest().unwrap()
+            format_date(date_column.get_earliest())
         );
         println!(
             "{};date;latest;{}",
             column_name,
-            date_column.get_latest().unwrap()
+            format_date(date_column.get_latest())
+        );
+        println!(
+            "{};date;date_order;{}",
+            column_name,
+            date_column.get_date_order().map_or("", |o| o.get_name())
+        );
+        println!(
+            "{};date;date_order_assumed;{}",
+            column_name,
+            date_column.is_date_order_assumed()
+        );
+        println!(
+            "{};date;ambiguous_count;{}",
+            column_name,
+            date_column.get_ambiguous_count()
         );
     }
+
+    for (column_name, datetime_column) in datetime_summary {
+        display_csv_profile(&column_name, "datetime", &datetime_column.get_profile());
+        println!(
+            "{};datetime;earliest;{}",
+            column_name,
+            format_datetime(datetime_column.get_earliest())
+        );
+        println!(
+            "{};datetime;latest;{}",
+            column_name,
+            format_datetime(datetime_column.get_latest())
+        );
+        println!(
+            "{};datetime;span_seconds;{}",
+            column_name,
+            datetime_column
+                .get_span()
+                .map_or(String::new(), |d| d.num_seconds().to_string())
+        );
+        println!(
+            "{};datetime;offsets;{}",
+            column_name,
+            format_offsets(&datetime_column.get_offsets())
+        );
+        for (hour, count) in datetime_column.get_hour_counts().iter().enumerate() {
+            println!("{};datetime;hour_{:02};{}", column_name, hour, count);
+        }
+    }
 }
 
 fn main() {
     // parses any arguments
     let args = Args::parse();
-    let (file_reader, headers) = load_file(&args.csv_path).expect("Failed to load the file");
-
-    let (mut handles_map, senders_map) = get_hashmaps(&headers);
-
-    // read the csv line by line, and send the values to respective threads
-    for line in file_reader.into_records() {
-        let clean_line = line.expect("Failed to get line");
-
-        // get index of the column name and the column name
-        for (index, header) in headers.iter().enumerate() {
-            // get value of a given column in a given line
-            let value = clean_line
-                .get(index)
-                .expect("Failed to get value from index");
-
-            // try to convert the value to float, if succeeds, use Float type, if it throws an
-            // error, use Text type
-            let converted_value = match value.parse::<f64>() {
-                Ok(v) => encapsulators::ColumnType::Float(v),
-                Err(_) => {
-                    let opt_date = parse_date_from_text(value);
-                    match opt_date {
-                        Some(d) => encapsulators::ColumnType::Date(d),
-                        None => encapsulators::ColumnType::Text(value.to_owned()),
-                    }
-                }
-            };
 
-            // send the value to the thread that manages this column
-            match senders_map[header].send(converted_value) {
-                Ok(_) => {}
-                Err(e) => {
-                    println!("Skipped a row because of sending to thread problem: {}", e);
-                }
-            }
+    // report the error and exit with the code matching its kind
+    if let Err(e) = run(args) {
+        eprintln!("error: {}", e);
+        process::exit(e.exit_code());
+    }
+}
+
+/// summarizes the file passed in the arguments and displays the results
+fn run(args: Args) -> Result<(), SummaryError> {
+    let mut summarizer = Summarizer::new();
+    if let Some(threads) = args.threads {
+        summarizer.set_threads(threads);
+    }
+
+    let mut dialect = Dialect::new();
+    match args.delimiter {
+        Delimiter::Auto => summarizer.set_sniff_delimiter(true),
+        Delimiter::Character(delimiter) => dialect.set_delimiter(delimiter),
+    }
+    dialect.set_quote(args.quote);
+    dialect.set_escape(args.escape);
+    dialect.set_double_quote(!args.no_double_quote);
+    dialect.set_comment(args.comment);
+
+    if args.no_header {
+        summarizer.set_has_headers(false);
+        match &args.header_file {
+            Some(path) => summarizer.set_column_names(Some(read_header_file(path, &dialect)?)),
+            None => summarizer.set_column_names(args.column_names),
         }
     }
+    summarizer.set_dialect(dialect);
 
-    // send a FileEnd message to every thread, so that they stop working
-    for header in headers.iter() {
-        match senders_map[header].send(encapsulators::ColumnType::FileEnd) {
-            Ok(_) => {}
-            Err(e) => {
-                println!(
-                    "Did not end iter end messgage because of sending to thread problem: {}",
-                    e
-                );
+    let output_format = if args.csv_format {
+        OutputFormat::Csv
+    } else {
+        args.format
+    };
+    // settings written into the JSON document, taken before the arguments are handed over
+    let report_settings = json::ReportSettings {
+        null_values: args.null_values.clone(),
+        type_threshold: args.type_threshold,
+        variance_kind: match args.std_kind {
+            StdKind::Population => "population",
+            StdKind::Sample => "sample",
+        },
+        percentiles: args.percentiles.clone(),
+        sketch_size: args.sketch_quantiles.then_some(args.sketch_size),
+        top_k: args.top_k,
+        distinct_threshold: args.distinct_threshold,
+        epoch_units: json::ColumnSetting::from_options(&args.epoch, |unit| match unit {
+            EpochUnit::Seconds => "seconds",
+            EpochUnit::Milliseconds => "milliseconds",
+        }),
+        date_orders: json::ColumnSetting::from_options(&args.date_order, |order| {
+            order.map(|order| order.get_name())
+        }),
+        date_formats: json::ColumnSetting::from_options(&args.date_format, String::clone),
+        number_locales: json::ColumnSetting::from_options(&args.number_locale, |locale| {
+            json::NumberLocaleSetting {
+                decimal_separator: locale.get_decimal_separator(),
+                grouping_separator: locale.get_grouping_separator(),
             }
+        }),
+    };
+
+    summarizer.set_null_values(args.null_values);
+    summarizer.set_type_threshold(args.type_threshold);
+    for (column_name, epoch_unit) in args.epoch {
+        summarizer.set_epoch_unit(column_name, epoch_unit);
+    }
+    for (column_name, date_order) in args.date_order {
+        summarizer.set_date_order(column_name, date_order);
+    }
+    for (column_name, date_format) in args.date_format {
+        summarizer.add_date_format(column_name, date_format);
+    }
+    for (column_name, number_locale) in args.number_locale {
+        summarizer.set_number_locale(column_name, number_locale);
+    }
+    summarizer.set_variance_kind(match args.std_kind {
+        StdKind::Population => VarianceKind::Population,
+        StdKind::Sample => VarianceKind::Sample,
+    });
+    summarizer.set_percentiles(args.percentiles);
+    summarizer.set_top_k(args.top_k);
+    summarizer.set_distinct_threshold(args.distinct_threshold);
+    if args.sketch_quantiles {
+        summarizer.set_sketch_size(Some(args.sketch_size));
+    }
+
+    let source = input::open(&args.csv_path)?;
+    let column_summaries = summarizer.summarize_reader(source)?;
+
+    // the JSON document and the reports keep the columns in header order
+    match output_format {
+        OutputFormat::Json => {
+            return json::display_json_stats(&args.csv_path, report_settings, &column_summaries);
+        }
+        OutputFormat::Markdown => {
+            markdown::display_markdown_stats(&args.csv_path, &column_summaries);
+            return Ok(());
         }
+        OutputFormat::Html => {
+            html::display_html_stats(&args.csv_path, &column_summaries);
+            return Ok(());
+        }
+        OutputFormat::Human | OutputFormat::Csv => {}
     }
 
     // prepare empty vectors for column summaries of different types
     let mut text_summary: Vec<(String, encapsulators::TextColumn)> = vec![];
     let mut number_summary: Vec<(String, encapsulators::NumberColumn)> = vec![];
     let mut date_summary: Vec<(String, encapsulators::DateColumn)> = vec![];
+    let mut datetime_summary: Vec<(String, encapsulators::DateTimeColumn)> = vec![];
+    let mut integer_summary: Vec<(String, encapsulators::IntegerColumn)> = vec![];
+    let mut boolean_summary: Vec<(String, encapsulators::BooleanColumn)> = vec![];
 
-    // joins all threads back into main, pushes the returned value to appropriate column type
-    // vector
-    for header in headers.iter() {
-        let column_summary = handles_map
-            .remove(header)
-            .expect("Did not get a handle from header")
-            .join();
-
+    // pushes the returned value to appropriate column type vector
+    for (header, column_summary) in column_summaries {
         match column_summary {
-            Ok(join_handle) => match join_handle {
-                encapsulators::ColumnSummary::Text(text_column) => {
-                    text_summary.push((header.to_owned(), text_column.build_summary()))
-                }
-                encapsulators::ColumnSummary::Number(number_column) => {
-                    number_summary.push((header.to_owned(), number_column.build_summary()))
-                }
-                encapsulators::ColumnSummary::Date(date_column) => {
-                    date_summary.push((header.to_owned(), date_column.build_summary()))
-                }
-            },
-            Err(_) => println!("Something went wrong during joining {} handle", &header),
+            encapsulators::ColumnSummary::Text(text_column) => {
+                text_summary.push((header, text_column))
+            }
+            encapsulators::ColumnSummary::Number(number_column) => {
+                number_summary.push((header, number_column))
+            }
+            encapsulators::ColumnSummary::Date(date_column) => {
+                date_summary.push((header, date_column))
+            }
+            encapsulators::ColumnSummary::DateTime(datetime_column) => {
+                datetime_summary.push((header, datetime_column))
+            }
+            encapsulators::ColumnSummary::Integer(integer_column) => {
+                integer_summary.push((header, integer_column))
+            }
+            encapsulators::ColumnSummary::Boolean(boolean_column) => {
+                boolean_summary.push((header, boolean_column))
+            }
         }
     }
 
     // displays all the results
-    if args.csv_format == true {
-        display_csv_stats(text_summary, number_summary, date_summary);
+    if output_format == OutputFormat::Csv {
+        display_csv_stats(
+            text_summary,
+            number_summary,
+            date_summary,
+            datetime_summary,
+            integer_summary,
+            boolean_summary,
+        );
     } else {
-        display_stats(text_summary, number_summary, date_summary);
+        display_stats(
+            text_summary,
+            number_summary,
+            date_summary,
+            datetime_summary,
+            integer_summary,
+            boolean_summary,
+        );
     }
+
+    return Ok(());
 }
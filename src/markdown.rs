@@ -0,0 +1,39 @@
+//! writes the summary as GitHub flavored markdown, one table per column type, ready to paste into
+//! pull requests and wiki pages.
+
+use crate::report;
+use csv_summary::encapsulators::ColumnSummary;
+
+/// escapes a cell so it stays inside its table column
+fn escape_cell(cell: &str) -> String {
+    return cell
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace('\r', "")
+        .replace('\n', "<br>");
+}
+
+/// prints one markdown table row
+fn print_row(cells: &[String]) {
+    let cells: Vec<String> = cells.iter().map(|cell| escape_cell(cell)).collect();
+    println!("| {} |", cells.join(" | "));
+}
+
+/// prints the summary of the file at path as markdown to standard output
+pub(crate) fn display_markdown_stats(path: &str, column_summaries: &[(String, ColumnSummary)]) {
+    let row_count = column_summaries
+        .first()
+        .map_or(0, |(_, summary)| summary.get_profile().get_row_count());
+    println!("# Summary of `{}`\n", path.replace('`', "'"));
+    println!("{} rows, {} columns\n", row_count, column_summaries.len());
+
+    for table in report::build_tables(column_summaries) {
+        println!("## {}\n", table.title);
+        print_row(&table.headers);
+        println!("|{}", " --- |".repeat(table.headers.len()));
+        for row in table.rows.iter() {
+            print_row(row);
+        }
+        println!();
+    }
+}
@@ -0,0 +1,689 @@
+//! turns raw csv fields into typed values that the aggregators understand.
+
+use crate::encapsulators::{self, DateOrder, NumberUnit, SemanticType};
+use crate::settings::{ColumnSettings, Settings};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// tokens treated as missing values unless the user passes their own
+pub const DEFAULT_NULL_VALUES: [&str; 6] = ["", "NA", "N/A", "NULL", "null", "-"];
+
+/// layouts of timestamps with a utc offset, tried after RFC 3339 and RFC 2822
+const ZONED_DATETIME_FORMATS: [&str; 3] = [
+    "%Y-%m-%dT%H:%M:%S%.f%#z",
+    "%Y-%m-%d %H:%M:%S%.f%#z",
+    "%Y-%m-%d %H:%M:%S%.f %#z",
+];
+
+/// layouts of timestamps without a utc offset
+const NAIVE_DATETIME_FORMATS: [&str; 6] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S%.f",
+    "%Y/%m/%d %H:%M",
+];
+
+/// week number specifiers that need a weekday to make a date
+const WEEK_SPECIFIERS: [&str; 3] = ["%W", "%U", "%V"];
+
+/// weekday specifiers
+const WEEKDAY_SPECIFIERS: [&str; 4] = ["%u", "%w", "%a", "%A"];
+
+/// ISO 3166-1 alpha-2 country codes, sorted
+const COUNTRY_CODES: [&str; 249] = [
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// currency symbols and ISO 4217 codes of common currencies, written before or after an amount
+const CURRENCY_SIGNS: [&str; 16] = [
+    "$", "€", "£", "¥", "₹", "₩", "₽", "USD", "EUR", "GBP", "JPY", "CHF", "CNY", "INR", "CAD",
+    "AUD",
+];
+
+/// schemes of values detected as urls
+const URL_SCHEMES: [&str; 4] = ["http://", "https://", "ftp://", "www."];
+
+/// unit of unix timestamps stored as numbers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EpochUnit {
+    Seconds,
+    Milliseconds,
+}
+
+/// separators of numbers unless the user sets a locale, 1,234.56
+pub const DEFAULT_NUMBER_LOCALE: NumberLocale = NumberLocale {
+    decimal_separator: '.',
+    grouping_separator: ',',
+};
+
+/// separators used to write numbers in a locale
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberLocale {
+    decimal_separator: char,
+    // any whitespace is accepted when this is whitespace, like the narrow space used in French
+    grouping_separator: char,
+}
+
+impl NumberLocale {
+    /// returns a new NumberLocale with the passed decimal and grouping separators
+    pub fn new(decimal_separator: char, grouping_separator: char) -> Self {
+        return NumberLocale {
+            decimal_separator,
+            grouping_separator,
+        };
+    }
+
+    /// returns the separator between the integer and the fractional part
+    pub fn get_decimal_separator(&self) -> char {
+        return self.decimal_separator;
+    }
+
+    /// returns the separator between groups of thousands
+    pub fn get_grouping_separator(&self) -> char {
+        return self.grouping_separator;
+    }
+
+    /// returns true if c separates groups of thousands in this locale
+    fn is_grouping_separator(&self, c: char) -> bool {
+        return c == self.grouping_separator
+            || (self.grouping_separator.is_whitespace() && c.is_whitespace());
+    }
+}
+
+/// returns Null type for values in null_values, otherwise tries the date formats and epoch unit of
+/// the column, integers, floats and numbers written in the column locale, then a boolean flag, a
+/// timestamp and a date, and falls back to Text type
+pub(crate) fn convert_value(
+    value: &str,
+    settings: &Settings,
+    column_settings: &ColumnSettings,
+) -> encapsulators::ColumnType {
+    if settings.null_values.contains(value) {
+        return encapsulators::ColumnType::Null;
+    }
+
+    // formats set by the user come first, so dates like 20240314 are not read as numbers
+    for format in column_settings.date_formats.iter() {
+        if let Some(date) = parse_date_with_format(value, format) {
+            return encapsulators::ColumnType::Date(date);
+        }
+    }
+
+    if let Some(epoch_unit) = column_settings.epoch_unit {
+        if let Some(timestamp) = parse_epoch(value, epoch_unit) {
+            return encapsulators::ColumnType::DateTime(
+                timestamp,
+                Some(FixedOffset::east_opt(0).unwrap()),
+            );
+        }
+    }
+
+    if let Ok(v) = value.parse::<i64>() {
+        return encapsulators::ColumnType::Integer(v);
+    }
+    // in locales with a decimal comma 1.234 is an integer, not a float. NaN and infinities, also
+    // from numbers too large for a float, would poison min, max and sum, they stay text
+    let number_locale = column_settings.number_locale;
+    if number_locale.get_decimal_separator() == '.' {
+        if let Ok(v) = value.parse::<f64>() {
+            if v.is_finite() {
+                return encapsulators::ColumnType::Float(v);
+            }
+        }
+    }
+    if let Some(number) = parse_number(value, number_locale) {
+        return number;
+    }
+    if let Some(flag) = parse_boolean(value) {
+        return encapsulators::ColumnType::Boolean(flag);
+    }
+    if let Some((timestamp, offset)) = parse_datetime_from_text(value) {
+        return encapsulators::ColumnType::DateTime(timestamp, offset);
+    }
+    return match parse_date_from_text(value) {
+        Some(date) => date,
+        None => encapsulators::ColumnType::Text(value.to_owned()),
+    };
+}
+
+/// returns the number written in the value with the separators of the locale, like 1,234.56 or
+/// 1.234,56, with an optional currency symbol or code before or after it, a trailing percent sign
+/// or parentheses for negatives like (100). Returns Quantity type for numbers with a unit, Integer
+/// type for whole numbers and Float type for the rest, None if the value is not such a number
+pub(crate) fn parse_number(value: &str, locale: NumberLocale) -> Option<encapsulators::ColumnType> {
+    let mut rest = value.trim();
+    let mut negative = false;
+    if let Some(inner) = rest.strip_prefix('(').and_then(|r| r.strip_suffix(')')) {
+        rest = inner.trim();
+        negative = true;
+    }
+    // parentheses count as a sign, numbers with more than one sign like (-5) or -$-5 are rejected
+    let mut sign_count = negative as u32;
+    let strip_sign = |rest: &mut &str, negative: &mut bool| -> bool {
+        if let Some(unsigned) = rest.strip_prefix('-') {
+            *rest = unsigned.trim_start();
+            *negative = true;
+            return true;
+        } else if let Some(unsigned) = rest.strip_prefix('+') {
+            *rest = unsigned.trim_start();
+            return true;
+        }
+        return false;
+    };
+    // the sign can be written before or after a leading currency sign, -$5 or $-5
+    sign_count += strip_sign(&mut rest, &mut negative) as u32;
+
+    let mut unit = None;
+    if let Some(number) = rest.strip_suffix('%') {
+        rest = number.trim_end();
+        unit = Some(NumberUnit::Percent);
+    } else {
+        for sign in CURRENCY_SIGNS {
+            if let Some(number) = rest.strip_prefix(sign).or_else(|| rest.strip_suffix(sign)) {
+                rest = number.trim();
+                unit = Some(NumberUnit::Currency(sign));
+                break;
+            }
+        }
+    }
+    if unit.is_some() {
+        sign_count += strip_sign(&mut rest, &mut negative) as u32;
+    }
+    if sign_count > 1 {
+        return None;
+    }
+
+    // rebuild the number in the layout parse understands, checking groups of thousands
+    let (integer_part, fraction_part) = match rest.split_once(locale.decimal_separator) {
+        Some((integer_part, fraction_part)) => (integer_part, Some(fraction_part)),
+        None => (rest, None),
+    };
+    let groups: Vec<&str> = integer_part
+        .split(|c| locale.is_grouping_separator(c))
+        .collect();
+    let valid_groups = groups.iter().enumerate().all(|(index, group)| {
+        let digits = group.chars().all(|c| c.is_ascii_digit());
+        match index {
+            0 if groups.len() > 1 => digits && (1..=3).contains(&group.len()),
+            0 => digits && !group.is_empty(),
+            _ => digits && group.len() == 3,
+        }
+    });
+    if !valid_groups
+        || fraction_part.is_some_and(|f| f.is_empty() || !f.chars().all(|c| c.is_ascii_digit()))
+    {
+        return None;
+    }
+
+    let mut number = String::with_capacity(rest.len() + 1);
+    if negative {
+        number.push('-');
+    }
+    groups.iter().for_each(|group| number.push_str(group));
+    if let Some(fraction_part) = fraction_part {
+        number.push('.');
+        number.push_str(fraction_part);
+    }
+
+    if unit.is_none() {
+        if let Ok(integer) = number.parse::<i64>() {
+            return Some(encapsulators::ColumnType::Integer(integer));
+        }
+    }
+    // hundreds of digits overflow to infinity
+    let float = number
+        .parse::<f64>()
+        .ok()
+        .filter(|float| float.is_finite())?;
+    return Some(match unit {
+        Some(unit) => encapsulators::ColumnType::Quantity(float, unit),
+        None => encapsulators::ColumnType::Float(float),
+    });
+}
+
+/// returns the boolean a flag word stands for: true/false in lower, title or upper case and
+/// yes/no in lower or title case
+pub(crate) fn parse_boolean(value: &str) -> Option<bool> {
+    return match value {
+        "true" | "True" | "TRUE" | "yes" | "Yes" => Some(true),
+        "false" | "False" | "FALSE" | "no" | "No" => Some(false),
+        _ => None,
+    };
+}
+
+/// returns the boolean a short flag token stands for: t/f and y/n in any case and YES/NO. These
+/// are also common text, like grades or country codes, so they are text unless every value of the
+/// column is a flag
+pub(crate) fn parse_short_boolean(value: &str) -> Option<bool> {
+    return match value {
+        "t" | "T" | "y" | "Y" | "YES" => Some(true),
+        "f" | "F" | "n" | "N" | "NO" => Some(false),
+        _ => None,
+    };
+}
+
+/// returns the semantic type recognized from the format of the value, None for values without one
+pub(crate) fn detect_semantic_type(value: &str) -> Option<SemanticType> {
+    if is_uuid(value) {
+        return Some(SemanticType::Uuid);
+    }
+    if value.parse::<Ipv4Addr>().is_ok() {
+        return Some(SemanticType::Ipv4);
+    }
+    if value.contains(':') && value.parse::<Ipv6Addr>().is_ok() {
+        return Some(SemanticType::Ipv6);
+    }
+    if is_email(value) {
+        return Some(SemanticType::Email);
+    }
+    if is_url(value) {
+        return Some(SemanticType::Url);
+    }
+    if is_phone(value) {
+        return Some(SemanticType::Phone);
+    }
+    if is_currency(value) {
+        return Some(SemanticType::Currency);
+    }
+    if COUNTRY_CODES.binary_search(&value).is_ok() {
+        return Some(SemanticType::CountryCode);
+    }
+    return None;
+}
+
+/// returns true for uuids in the 8-4-4-4-12 hex digit layout
+fn is_uuid(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    if bytes.len() != 36 {
+        return false;
+    }
+    return bytes.iter().enumerate().all(|(index, byte)| match index {
+        8 | 13 | 18 | 23 => *byte == b'-',
+        _ => byte.is_ascii_hexdigit(),
+    });
+}
+
+/// returns true for addresses with a local part, a single @ and a domain of dot separated labels
+fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    if local.is_empty() || local.contains(char::is_whitespace) || !domain.contains('.') {
+        return false;
+    }
+    return domain
+        .split('.')
+        .all(|label| !label.is_empty() && label.chars().all(|c| c.is_alphanumeric() || c == '-'));
+}
+
+/// returns true for values starting with a url scheme or www. followed by a host without whitespace
+fn is_url(value: &str) -> bool {
+    let lowercase_start = value
+        .get(..8.min(value.len()))
+        .map(|start| start.to_ascii_lowercase());
+    let Some(start) = lowercase_start else {
+        return false;
+    };
+    return URL_SCHEMES.iter().any(|scheme| {
+        start.starts_with(scheme)
+            && value.len() > scheme.len()
+            && !value.contains(char::is_whitespace)
+    });
+}
+
+/// returns true for phone numbers: 10 to 15 digits, or 7 to 15 behind a leading +, separated only
+/// by spaces, dots, dashes and parentheses
+fn is_phone(value: &str) -> bool {
+    let international = value.starts_with('+');
+    let number = value.strip_prefix('+').unwrap_or(value);
+    let mut digit_count = 0;
+    for c in number.chars() {
+        match c {
+            '0'..='9' => digit_count += 1,
+            ' ' | '.' | '-' | '(' | ')' => {}
+            _ => return false,
+        }
+    }
+    let min_digits = if international { 7 } else { 10 };
+    return (min_digits..=15).contains(&digit_count);
+}
+
+/// returns true for amounts with a currency symbol or code before or after them, like $1,200.50,
+/// -€3 or 12,50 EUR
+fn is_currency(value: &str) -> bool {
+    let unsigned = value.strip_prefix('-').unwrap_or(value);
+    return CURRENCY_SIGNS.iter().any(|sign| {
+        let amount = unsigned
+            .strip_prefix(sign)
+            .or_else(|| unsigned.strip_suffix(sign));
+        amount.is_some_and(|amount| {
+            let amount = amount.trim();
+            let amount = amount.strip_prefix('-').unwrap_or(amount);
+            amount.starts_with(|c: char| c.is_ascii_digit())
+                && amount
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || c == ',' || c == '.')
+        })
+    });
+}
+
+/// writes the shape mask of the value into mask: uppercase letters become A, other letters a,
+/// digits 9 and every other character is kept, so "AB-12c" becomes "AA-99a"
+pub(crate) fn write_shape_mask(value: &str, mask: &mut String) {
+    mask.clear();
+    mask.extend(value.chars().map(|c| {
+        if c.is_uppercase() {
+            'A'
+        } else if c.is_alphabetic() {
+            'a'
+        } else if c.is_numeric() {
+            '9'
+        } else {
+            c
+        }
+    }));
+}
+
+/// returns the date written in the text in the chrono format. Formats with a week number but no
+/// weekday give the monday of that week
+pub(crate) fn parse_date_with_format(text_date: &str, format: &str) -> Option<NaiveDate> {
+    if let Ok(date) = NaiveDate::parse_from_str(text_date, format) {
+        return Some(date);
+    }
+
+    let has_week = WEEK_SPECIFIERS.iter().any(|s| format.contains(s));
+    let has_weekday = WEEKDAY_SPECIFIERS.iter().any(|s| format.contains(s));
+    if has_week && !has_weekday {
+        let week_format = format!("{} %u", format);
+        let week_date = format!("{} 1", text_date);
+        return NaiveDate::parse_from_str(&week_date, &week_format).ok();
+    }
+    return None;
+}
+
+/// returns the time written in a unix timestamp of the passed unit, in utc. Seconds may have a
+/// fractional part
+pub(crate) fn parse_epoch(value: &str, epoch_unit: EpochUnit) -> Option<NaiveDateTime> {
+    let timestamp = match (value.parse::<i64>(), epoch_unit) {
+        (Ok(seconds), EpochUnit::Seconds) => DateTime::from_timestamp(seconds, 0),
+        (Ok(milliseconds), EpochUnit::Milliseconds) => {
+            DateTime::from_timestamp_millis(milliseconds)
+        }
+        (Err(_), _) => {
+            let number = value
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())?;
+            let milliseconds = match epoch_unit {
+                EpochUnit::Seconds => number * 1000.0,
+                EpochUnit::Milliseconds => number,
+            };
+            DateTime::from_timestamp_millis(milliseconds.round() as i64)
+        }
+    };
+    return timestamp.map(|timestamp| timestamp.naive_utc());
+}
+
+/// returns the local date and time written in the text, with its utc offset if it has one. Accepts
+/// RFC 3339 (ISO 8601 with an offset), RFC 2822 and `YYYY-MM-DD HH:MM[:SS]` layouts with a space or
+/// `T` between date and time
+pub(crate) fn parse_datetime_from_text(
+    text_datetime: &str,
+) -> Option<(NaiveDateTime, Option<FixedOffset>)> {
+    // every layout has a time with a colon, skips the format attempts for most other text
+    if !text_datetime.contains(':') {
+        return None;
+    }
+
+    let zoned = DateTime::parse_from_rfc3339(text_datetime)
+        .or_else(|_| DateTime::parse_from_rfc2822(text_datetime))
+        .ok()
+        .or_else(|| {
+            ZONED_DATETIME_FORMATS
+                .iter()
+                .find_map(|format| DateTime::parse_from_str(text_datetime, format).ok())
+        });
+    if let Some(timestamp) = zoned {
+        return Some((timestamp.naive_local(), Some(*timestamp.offset())));
+    }
+
+    return NAIVE_DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text_datetime, format).ok())
+        .map(|timestamp| (timestamp, None));
+}
+
+/// assembles a date from the first three digit groups of the text. Dates in yyyy-(m)m-(d)d order
+/// give Date type, dates with the year last give UnorderedDate type, as the order of day and month
+/// is decided for the whole column
+pub(crate) fn parse_date_from_text(text_date: &str) -> Option<encapsulators::ColumnType> {
+    let mut split_date_iterator = text_date.split(|c: char| !c.is_ascii_digit());
+    let first_sequence = split_date_iterator.next()?;
+    let second_sequence = split_date_iterator.next()?;
+    let third_sequence = split_date_iterator.next()?;
+
+    // year-like structure passed first, the format is yyyy-(m)m-(d)d
+    if first_sequence.len() == 4 {
+        let mut date_assembler = encapsulators::PossibleDate::new();
+        date_assembler.set_year(first_sequence.parse::<i32>().ok()?);
+        date_assembler.set_month(second_sequence.parse::<u32>().ok()?);
+        date_assembler.set_day(third_sequence.parse::<u32>().ok()?);
+
+        let constructed_date = NaiveDate::from_ymd_opt(
+            date_assembler.get_year()?,
+            date_assembler.get_month()?,
+            date_assembler.get_day()?,
+        );
+        return constructed_date.map(encapsulators::ColumnType::Date);
+    }
+
+    // year-like structure passed last, the format is (d)d-(m)m-yyyy or (m)m-(d)d-yyyy
+    if third_sequence.len() == 4 {
+        let first = first_sequence.parse::<u32>().ok()?;
+        let second = second_sequence.parse::<u32>().ok()?;
+        let year = third_sequence.parse::<i32>().ok()?;
+
+        // the numbers have to make a date in at least one order
+        let day_first = DateOrder::DayFirst.build_date(first, second, year);
+        let month_first = DateOrder::MonthFirst.build_date(first, second, year);
+        if day_first.is_none() && month_first.is_none() {
+            return None;
+        }
+        return Some(encapsulators::ColumnType::UnorderedDate(
+            first, second, year,
+        ));
+    }
+
+    return None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encapsulators::ColumnType;
+
+    const GERMAN: NumberLocale = NumberLocale {
+        decimal_separator: ',',
+        grouping_separator: '.',
+    };
+
+    #[test]
+    fn parse_number_reads_locale_numbers() {
+        let cases: [(&str, NumberLocale, f64); 8] = [
+            ("1,234.5", DEFAULT_NUMBER_LOCALE, 1234.5),
+            ("1.234,5", GERMAN, 1234.5),
+            ("1.234", GERMAN, 1234.0),
+            ("(100)", DEFAULT_NUMBER_LOCALE, -100.0),
+            ("-1,000", DEFAULT_NUMBER_LOCALE, -1000.0),
+            ("0.25", DEFAULT_NUMBER_LOCALE, 0.25),
+            ("12 345,6", NumberLocale::new(',', ' '), 12345.6),
+            ("+7", DEFAULT_NUMBER_LOCALE, 7.0),
+        ];
+        for (value, locale, expected) in cases {
+            let number = match parse_number(value, locale) {
+                Some(ColumnType::Integer(integer)) => integer as f64,
+                Some(ColumnType::Float(float)) => float,
+                other => panic!("{} parsed as {:?}", value, other),
+            };
+            assert_eq!(number, expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn convert_value_keeps_non_finite_numbers_as_text() {
+        let settings = Settings::new();
+        let column_settings = settings.get_column_settings("column");
+        let huge = "9".repeat(400);
+        for value in [
+            "NaN",
+            "nan",
+            "inf",
+            "-inf",
+            "infinity",
+            "1e400",
+            huge.as_str(),
+        ] {
+            let converted = convert_value(value, &settings, &column_settings);
+            assert!(
+                matches!(converted, ColumnType::Text(_)),
+                "{} converted to {:?}",
+                value,
+                converted
+            );
+        }
+        assert!(matches!(
+            convert_value("1e300", &settings, &column_settings),
+            ColumnType::Float(_)
+        ));
+    }
+
+    #[test]
+    fn parse_number_reads_units() {
+        let cases: [(&str, f64, NumberUnit); 5] = [
+            ("45%", 45.0, NumberUnit::Percent),
+            ("-2.5 %", -2.5, NumberUnit::Percent),
+            ("$1,200.00", 1200.0, NumberUnit::Currency("$")),
+            ("-$5", -5.0, NumberUnit::Currency("$")),
+            ("(12 EUR)", -12.0, NumberUnit::Currency("EUR")),
+        ];
+        for (value, expected, expected_unit) in cases {
+            match parse_number(value, DEFAULT_NUMBER_LOCALE) {
+                Some(ColumnType::Quantity(number, unit)) => {
+                    assert_eq!(number, expected, "{}", value);
+                    assert_eq!(unit, expected_unit, "{}", value);
+                }
+                other => panic!("{} parsed as {:?}", value, other),
+            }
+        }
+    }
+
+    #[test]
+    fn parse_number_rejects_malformed_numbers() {
+        let cases: [(&str, NumberLocale); 9] = [
+            ("(-5)", DEFAULT_NUMBER_LOCALE),
+            ("(+5)", DEFAULT_NUMBER_LOCALE),
+            ("($-5)", DEFAULT_NUMBER_LOCALE),
+            ("-$-5", DEFAULT_NUMBER_LOCALE),
+            ("1.234,5", DEFAULT_NUMBER_LOCALE),
+            ("1,234.5", GERMAN),
+            ("12,34", DEFAULT_NUMBER_LOCALE),
+            ("1.", DEFAULT_NUMBER_LOCALE),
+            ("abc", DEFAULT_NUMBER_LOCALE),
+        ];
+        for (value, locale) in cases {
+            assert!(parse_number(value, locale).is_none(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn parse_date_from_text_leaves_the_order_open() {
+        assert!(matches!(
+            parse_date_from_text("03/04/2024"),
+            Some(ColumnType::UnorderedDate(3, 4, 2024))
+        ));
+        assert!(matches!(
+            parse_date_from_text("2024-04-03"),
+            Some(ColumnType::Date(date)) if date == NaiveDate::from_ymd_opt(2024, 4, 3).unwrap()
+        ));
+        // dates that do not exist in either order are not dates
+        assert!(parse_date_from_text("31/02/2024").is_none());
+        assert!(parse_date_from_text("2024-02-30").is_none());
+    }
+
+    #[test]
+    fn parse_boolean_reads_flag_words_only() {
+        for value in ["true", "True", "TRUE", "yes", "Yes"] {
+            assert_eq!(parse_boolean(value), Some(true), "{}", value);
+        }
+        for value in ["false", "False", "FALSE", "no", "No"] {
+            assert_eq!(parse_boolean(value), Some(false), "{}", value);
+        }
+        for value in ["y", "F", "NO", "tRUE", "1", "on"] {
+            assert_eq!(parse_boolean(value), None, "{}", value);
+        }
+        assert_eq!(parse_short_boolean("NO"), Some(false));
+        assert_eq!(parse_short_boolean("y"), Some(true));
+        assert_eq!(parse_short_boolean("no"), None);
+    }
+
+    #[test]
+    fn detect_semantic_type_recognizes_every_type() {
+        let cases: [(&str, SemanticType); 16] = [
+            ("jane.doe@example.com", SemanticType::Email),
+            ("a@b.co", SemanticType::Email),
+            ("https://example.com/path?q=1", SemanticType::Url),
+            ("www.example.org", SemanticType::Url),
+            ("123e4567-e89b-12d3-a456-426614174000", SemanticType::Uuid),
+            ("192.168.0.1", SemanticType::Ipv4),
+            ("2001:db8::1", SemanticType::Ipv6),
+            ("::1", SemanticType::Ipv6),
+            ("+1 555 123 4567", SemanticType::Phone),
+            ("(555) 123-4567", SemanticType::Phone),
+            ("5551234567", SemanticType::Phone),
+            ("$1,200.50", SemanticType::Currency),
+            ("12,50 EUR", SemanticType::Currency),
+            ("-€3", SemanticType::Currency),
+            ("NO", SemanticType::CountryCode),
+            ("DE", SemanticType::CountryCode),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(detect_semantic_type(value), Some(expected), "{}", value);
+        }
+    }
+
+    #[test]
+    fn detect_semantic_type_ignores_plain_values() {
+        let cases = [
+            "hello world",
+            "a@b",
+            "@example.com",
+            "http://",
+            "12345",
+            "1.2.3",
+            "123e4567-e89b-12d3-a456-42661417400g",
+            "EUR",
+            "XX",
+            "de",
+        ];
+        for value in cases {
+            assert_eq!(detect_semantic_type(value), None, "{}", value);
+        }
+    }
+}
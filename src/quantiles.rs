@@ -0,0 +1,394 @@
+//! estimates quantiles of the number values of a column. By default every distinct value is
+//! counted and the quantiles are exact, a KLL sketch keeps memory bounded at the cost of accuracy.
+
+use std::collections::HashMap;
+
+/// percentiles reported for number columns unless the user passes their own
+pub const DEFAULT_PERCENTILES: [f64; 7] = [1.0, 5.0, 25.0, 50.0, 75.0, 95.0, 99.0];
+
+/// number of equal width bins in the histogram of a number column
+pub const HISTOGRAM_BINS: usize = 20;
+
+/// returns the bin of a value in bins equal width bins from min to max, max falls in the last bin
+fn get_bin(value: f64, min: f64, max: f64, bins: usize) -> usize {
+    if max <= min {
+        return 0;
+    }
+    let bin = ((value - min) / (max - min) * bins as f64) as usize;
+    return bin.min(bins - 1);
+}
+
+/// counts of every distinct number value, keyed by the bits of the value
+pub(crate) struct ExactQuantiles {
+    counts: HashMap<u64, u64>,
+}
+
+impl ExactQuantiles {
+    /// returns a new ExactQuantiles object without any values
+    pub(crate) fn new() -> Self {
+        return ExactQuantiles {
+            counts: HashMap::new(),
+        };
+    }
+
+    /// counts one value
+    pub(crate) fn add(&mut self, value: f64) {
+        // -0.0 and 0.0 are the same value with different bits
+        let value = if value == 0.0 { 0.0 } else { value };
+        *self.counts.entry(value.to_bits()).or_insert(0) += 1;
+    }
+
+    /// adds the counts of another part of the same column
+    pub(crate) fn merge(&mut self, other: ExactQuantiles) {
+        for (bits, count) in other.counts {
+            *self.counts.entry(bits).or_insert(0) += count;
+        }
+    }
+
+    /// returns the values at the passed percentiles (0 to 100), interpolating linearly between the
+    /// two closest values. Returns None if no values were counted
+    pub(crate) fn get_percentiles(&self, percentiles: &[f64]) -> Option<Vec<f64>> {
+        if self.counts.is_empty() {
+            return None;
+        }
+
+        // distinct values in ascending order with the number of values up to and including them
+        let mut values: Vec<(f64, u64)> = self
+            .counts
+            .iter()
+            .map(|(bits, count)| (f64::from_bits(*bits), *count))
+            .collect();
+        values.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut cumulative_count: u64 = 0;
+        for (_, count) in values.iter_mut() {
+            cumulative_count += *count;
+            *count = cumulative_count;
+        }
+
+        // returns the value at the position in the sorted list of all values
+        let value_at = |position: u64| -> f64 {
+            let index = values.partition_point(|(_, count)| *count <= position);
+            return values[index].0;
+        };
+
+        let last_position = cumulative_count - 1;
+        let results = percentiles
+            .iter()
+            .map(|percentile| {
+                let rank = percentile.clamp(0.0, 100.0) / 100.0 * last_position as f64;
+                let lower = value_at(rank.floor() as u64);
+                let upper = value_at(rank.ceil() as u64);
+                if lower == upper {
+                    return lower;
+                }
+                return lower + (upper - lower) * rank.fract();
+            })
+            .collect();
+        return Some(results);
+    }
+
+    /// returns the number of values in each of bins equal width bins from min to max
+    pub(crate) fn get_histogram(&self, min: f64, max: f64, bins: usize) -> Vec<u64> {
+        let mut histogram = vec![0; bins];
+        for (bits, count) in self.counts.iter() {
+            histogram[get_bin(f64::from_bits(*bits), min, max, bins)] += count;
+        }
+        return histogram;
+    }
+}
+
+/// default number of values kept by the top level compactor of a sketch
+pub const DEFAULT_SKETCH_SIZE: usize = 200;
+
+/// each compactor is this share of the size of the one above it
+const SKETCH_CAPACITY_RATIO: f64 = 2.0 / 3.0;
+
+/// KLL sketch of the number values of a column. Keeps a stack of compactors, a value in level h
+/// stands for 2^h values, and halves a full level by keeping every other sorted value. Memory grows
+/// with the logarithm of the number of values instead of with the number of distinct values
+pub(crate) struct KllSketch {
+    k: usize,
+    compactors: Vec<Vec<f64>>,
+    size: usize,
+    max_size: usize,
+    // state of the xorshift generator that picks which half of a level is kept
+    random_state: u64,
+}
+
+impl KllSketch {
+    /// returns a new KllSketch whose top level keeps k values
+    pub(crate) fn new(k: usize) -> Self {
+        let mut sketch = KllSketch {
+            k,
+            compactors: vec![],
+            size: 0,
+            max_size: 0,
+            random_state: 0x9e37_79b9_7f4a_7c15,
+        };
+        sketch.grow();
+        return sketch;
+    }
+
+    /// returns the approximate normalized rank error of a sketch of size k, the share of all values
+    /// a returned percentile can be off by with 99% confidence
+    pub(crate) fn get_rank_error(k: usize) -> f64 {
+        return 2.296 / (k as f64).powf(0.9723);
+    }
+
+    /// returns the number of values level h can hold before it is compacted
+    fn get_capacity(&self, h: usize) -> usize {
+        let depth = self.compactors.len() - h - 1;
+        return (SKETCH_CAPACITY_RATIO.powi(depth as i32) * self.k as f64).ceil() as usize + 1;
+    }
+
+    /// adds a new top level to the sketch
+    fn grow(&mut self) {
+        self.compactors.push(vec![]);
+        self.max_size = (0..self.compactors.len())
+            .map(|h| self.get_capacity(h))
+            .sum();
+    }
+
+    /// adds one value
+    pub(crate) fn add(&mut self, value: f64) {
+        self.compactors[0].push(value);
+        self.size += 1;
+        if self.size >= self.max_size {
+            self.compress();
+        }
+    }
+
+    /// adds the values kept by another sketch of the same column
+    pub(crate) fn merge(&mut self, other: KllSketch) {
+        while self.compactors.len() < other.compactors.len() {
+            self.grow();
+        }
+        for (compactor, other_compactor) in self.compactors.iter_mut().zip(other.compactors) {
+            compactor.extend(other_compactor);
+        }
+
+        self.size = self.compactors.iter().map(|c| c.len()).sum();
+        while self.size >= self.max_size {
+            self.compress();
+        }
+    }
+
+    /// compacts the lowest full level, moving half of its pairs of values one level up
+    fn compress(&mut self) {
+        for h in 0..self.compactors.len() {
+            if self.compactors[h].len() < self.get_capacity(h) {
+                continue;
+            }
+            if h + 1 >= self.compactors.len() {
+                self.grow();
+            }
+
+            let mut values = std::mem::take(&mut self.compactors[h]);
+            values.sort_by(|a, b| a.total_cmp(b));
+            // only pairs are compacted, an odd value stays on its level so no weight is lost
+            if values.len() % 2 == 1 {
+                self.compactors[h].extend(values.pop());
+            }
+            let offset = (self.next_random() & 1) as usize;
+            self.compactors[h + 1].extend(values.into_iter().skip(offset).step_by(2));
+
+            self.size = self.compactors.iter().map(|c| c.len()).sum();
+            return;
+        }
+    }
+
+    /// returns the next value of the xorshift generator
+    fn next_random(&mut self) -> u64 {
+        self.random_state ^= self.random_state << 13;
+        self.random_state ^= self.random_state >> 7;
+        self.random_state ^= self.random_state << 17;
+        return self.random_state;
+    }
+
+    /// returns the values at the passed percentiles (0 to 100), each one a value kept by the
+    /// sketch. Returns None if the sketch is empty
+    pub(crate) fn get_percentiles(&self, percentiles: &[f64]) -> Option<Vec<f64>> {
+        // kept values in ascending order with the total weight up to and including them
+        let mut values: Vec<(f64, u64)> = vec![];
+        for (h, compactor) in self.compactors.iter().enumerate() {
+            values.extend(compactor.iter().map(|value| (*value, 1u64 << h)));
+        }
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut total_weight: u64 = 0;
+        for (_, weight) in values.iter_mut() {
+            total_weight += *weight;
+            *weight = total_weight;
+        }
+
+        let results = percentiles
+            .iter()
+            .map(|percentile| {
+                let rank = percentile.clamp(0.0, 100.0) / 100.0 * total_weight as f64;
+                let index = values.partition_point(|(_, weight)| (*weight as f64) < rank);
+                return values[index.min(values.len() - 1)].0;
+            })
+            .collect();
+        return Some(results);
+    }
+
+    /// returns the approximate number of values in each of bins equal width bins from min to max,
+    /// every kept value counts with its weight
+    pub(crate) fn get_histogram(&self, min: f64, max: f64, bins: usize) -> Vec<u64> {
+        let mut histogram = vec![0; bins];
+        for (h, compactor) in self.compactors.iter().enumerate() {
+            for value in compactor {
+                histogram[get_bin(*value, min, max, bins)] += 1u64 << h;
+            }
+        }
+        return histogram;
+    }
+}
+
+/// quantile estimator of a column, exact or sketch based
+pub(crate) enum QuantileEstimator {
+    Exact(ExactQuantiles),
+    Sketch(KllSketch),
+}
+
+impl QuantileEstimator {
+    /// returns an exact estimator when sketch_size is None, otherwise a KLL sketch of that size
+    pub(crate) fn new(sketch_size: Option<usize>) -> Self {
+        return match sketch_size {
+            Some(k) => QuantileEstimator::Sketch(KllSketch::new(k)),
+            None => QuantileEstimator::Exact(ExactQuantiles::new()),
+        };
+    }
+
+    /// adds one value
+    pub(crate) fn add(&mut self, value: f64) {
+        match self {
+            QuantileEstimator::Exact(exact) => exact.add(value),
+            QuantileEstimator::Sketch(sketch) => sketch.add(value),
+        }
+    }
+
+    /// adds the values of another estimator of the same kind
+    pub(crate) fn merge(&mut self, other: QuantileEstimator) {
+        match (self, other) {
+            (QuantileEstimator::Exact(exact), QuantileEstimator::Exact(other)) => {
+                exact.merge(other)
+            }
+            (QuantileEstimator::Sketch(sketch), QuantileEstimator::Sketch(other)) => {
+                sketch.merge(other)
+            }
+            _ => unreachable!("all estimators of a summary are built from the same settings"),
+        }
+    }
+
+    /// returns the values at the passed percentiles (0 to 100), None if there are no values
+    pub(crate) fn get_percentiles(&self, percentiles: &[f64]) -> Option<Vec<f64>> {
+        return match self {
+            QuantileEstimator::Exact(exact) => exact.get_percentiles(percentiles),
+            QuantileEstimator::Sketch(sketch) => sketch.get_percentiles(percentiles),
+        };
+    }
+
+    /// returns the number of values in each of bins equal width bins from min to max, approximate
+    /// for a sketch
+    pub(crate) fn get_histogram(&self, min: f64, max: f64, bins: usize) -> Vec<u64> {
+        return match self {
+            QuantileEstimator::Exact(exact) => exact.get_histogram(min, max, bins),
+            QuantileEstimator::Sketch(sketch) => sketch.get_histogram(min, max, bins),
+        };
+    }
+
+    /// returns the normalized rank error of the percentiles, None for exact percentiles
+    pub(crate) fn get_rank_error(&self) -> Option<f64> {
+        return match self {
+            QuantileEstimator::Exact(_) => None,
+            QuantileEstimator::Sketch(sketch) => Some(KllSketch::get_rank_error(sketch.k)),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// returns 0..n in a fixed shuffled order
+    fn shuffled(n: u64) -> Vec<f64> {
+        // multiplying by a number coprime to n visits every value once
+        return (0..n).map(|i| ((i * 7919) % n) as f64).collect();
+    }
+
+    #[test]
+    fn exact_percentiles_interpolate_between_values() {
+        let mut first = ExactQuantiles::new();
+        let mut second = ExactQuantiles::new();
+        [40.0, 15.0, 50.0].iter().for_each(|v| first.add(*v));
+        [35.0, 20.0].iter().for_each(|v| second.add(*v));
+        first.merge(second);
+
+        let results = first
+            .get_percentiles(&[0.0, 25.0, 40.0, 50.0, 75.0, 90.0, 100.0])
+            .unwrap();
+        // the values numpy.percentile returns with its default linear method
+        let expected = [15.0, 20.0, 29.0, 35.0, 40.0, 46.0, 50.0];
+        for (value, expected) in results.iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-9, "{} != {}", value, expected);
+        }
+    }
+
+    #[test]
+    fn exact_percentiles_count_repeated_values() {
+        let mut quantiles = ExactQuantiles::new();
+        [1.0, 2.0, 2.0, 3.0, -0.0, 0.0]
+            .iter()
+            .for_each(|v| quantiles.add(*v));
+
+        let results = quantiles
+            .get_percentiles(&[0.0, 10.0, 50.0, 100.0])
+            .unwrap();
+        assert_eq!(results, vec![0.0, 0.0, 1.5, 3.0]);
+        assert_eq!(quantiles.get_histogram(0.0, 3.0, 3), vec![2, 1, 3]);
+        assert!(ExactQuantiles::new().get_percentiles(&[50.0]).is_none());
+    }
+
+    #[test]
+    fn kll_sketch_keeps_the_total_weight() {
+        let mut sketch = KllSketch::new(50);
+        (0..10_001).for_each(|i| sketch.add(i as f64));
+        let histogram = sketch.get_histogram(0.0, 10_000.0, 1);
+        assert_eq!(histogram, vec![10_001]);
+    }
+
+    #[test]
+    fn kll_sketch_percentiles_are_within_the_rank_error() {
+        let n = 100_000;
+        let values = shuffled(n);
+        let mut first = KllSketch::new(DEFAULT_SKETCH_SIZE);
+        let mut second = KllSketch::new(DEFAULT_SKETCH_SIZE);
+        for (i, value) in values.into_iter().enumerate() {
+            if i % 3 == 0 {
+                first.add(value);
+            } else {
+                second.add(value);
+            }
+        }
+        first.merge(second);
+
+        let percentiles: Vec<f64> = (1..100).map(|p| p as f64).collect();
+        let results = first.get_percentiles(&percentiles).unwrap();
+        let max_error = KllSketch::get_rank_error(DEFAULT_SKETCH_SIZE) * n as f64;
+        for (percentile, value) in percentiles.iter().zip(results) {
+            // the value of rank r in 0..n is r itself
+            let expected = percentile / 100.0 * n as f64;
+            assert!(
+                (value - expected).abs() <= max_error,
+                "p{} was {}, expected {} ± {}",
+                percentile,
+                value,
+                expected,
+                max_error
+            );
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! builds the tables of the markdown and html reports. Every table has one row per column of the
+//! summarized file, cells are already formatted so the reports only differ in markup.
+
+use crate::{
+    format_category_count, format_date, format_date_order, format_datetime, format_duration,
+    format_offsets, format_percentile, format_rank_error, format_semantic_type, format_statistic,
+    format_top_patterns, format_top_values,
+};
+use csv_summary::encapsulators::{self, ColumnSummary};
+
+/// table of a report with its title, column headers and formatted cells
+pub(crate) struct Table {
+    pub(crate) title: &'static str,
+    pub(crate) headers: Vec<String>,
+    pub(crate) rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// returns a new Table without rows
+    fn new(title: &'static str, headers: &[&str]) -> Self {
+        return Table {
+            title,
+            headers: headers.iter().map(|header| header.to_string()).collect(),
+            rows: vec![],
+        };
+    }
+}
+
+/// returns the tables of the report, in the order of the human readable output. Tables without
+/// rows are left out
+pub(crate) fn build_tables(column_summaries: &[(String, ColumnSummary)]) -> Vec<Table> {
+    let mut text_table = Table::new(
+        "Text columns",
+        &[
+            "column",
+            "nulls",
+            "null %",
+            "class count",
+            "semantic type",
+            "min length",
+            "max length",
+            "mean length",
+            "empty",
+            "leading space",
+            "trailing space",
+            "ascii",
+            "top values",
+        ],
+    );
+    let mut pattern_table = Table::new(
+        "Text patterns",
+        &["column", "patterns", "untracked", "top patterns"],
+    );
+    let mut date_table = Table::new(
+        "Date columns",
+        &[
+            "column",
+            "nulls",
+            "null %",
+            "earliest",
+            "latest",
+            "order",
+            "ambiguous",
+        ],
+    );
+    let mut datetime_table = Table::new(
+        "Datetime columns",
+        &[
+            "column", "nulls", "null %", "earliest", "latest", "span", "offsets",
+        ],
+    );
+    let number_headers = [
+        "column", "nulls", "null %", "count", "sum", "min", "max", "range", "min line", "max line",
+        "mean", "median", "variance", "std", "unit",
+    ];
+    let mut number_table = Table::new("Number columns", &number_headers);
+    let mut integer_table = Table::new("Integer columns", &number_headers[..14]);
+    let mut percentile_table = Table::new("Percentiles", &["column", "rank error", "iqr"]);
+    let mut boolean_table = Table::new(
+        "Boolean columns",
+        &["column", "nulls", "null %", "true", "false", "true ratio"],
+    );
+    let mut top_value_table = Table::new("Top values", &["column", "top values"]);
+    let mut mixed_table = Table::new("Mixed columns", &["column", "non-conforming", "examples"]);
+
+    for (column_name, summary) in column_summaries {
+        let profile = summary.get_profile();
+        let name = column_name.clone();
+        let nulls = profile.get_null_count().to_string();
+        let null_percentage = format!("{:.2}", profile.get_null_percentage());
+
+        match summary {
+            ColumnSummary::Text(text_column) => {
+                text_table.rows.push(vec![
+                    name.clone(),
+                    nulls,
+                    null_percentage,
+                    format_category_count(text_column),
+                    format_semantic_type(text_column),
+                    text_column.get_min_length().to_string(),
+                    text_column.get_max_length().to_string(),
+                    format!("{:.2}", text_column.get_mean_length()),
+                    text_column.get_empty_count().to_string(),
+                    text_column.get_leading_whitespace_count().to_string(),
+                    text_column.get_trailing_whitespace_count().to_string(),
+                    text_column.is_ascii().to_string(),
+                    format_top_values(&profile),
+                ]);
+                pattern_table.rows.push(vec![
+                    name,
+                    text_column.get_pattern_count().to_string(),
+                    text_column.get_untracked_pattern_count().to_string(),
+                    format_top_patterns(text_column),
+                ]);
+                // text columns accept every value, so they have no top values or mixed rows
+                continue;
+            }
+            ColumnSummary::Date(date_column) => date_table.rows.push(vec![
+                name.clone(),
+                nulls,
+                null_percentage,
+                format_date(date_column.get_earliest()),
+                format_date(date_column.get_latest()),
+                format_date_order(date_column),
+                date_column.get_ambiguous_count().to_string(),
+            ]),
+            ColumnSummary::DateTime(datetime_column) => datetime_table.rows.push(vec![
+                name.clone(),
+                nulls,
+                null_percentage,
+                format_datetime(datetime_column.get_earliest()),
+                format_datetime(datetime_column.get_latest()),
+                format_duration(datetime_column.get_span()),
+                format_offsets(&datetime_column.get_offsets()),
+            ]),
+            ColumnSummary::Number(number_column) => {
+                number_table.rows.push(vec![
+                    name.clone(),
+                    nulls,
+                    null_percentage,
+                    number_column.get_count().to_string(),
+                    format!("{:.4}", number_column.get_sum()),
+                    format!("{:.4}", number_column.get_min()),
+                    format!("{:.4}", number_column.get_max()),
+                    format!("{:.4}", number_column.get_range()),
+                    number_column.get_min_line().to_string(),
+                    number_column.get_max_line().to_string(),
+                    format!("{:.4}", number_column.get_mean()),
+                    format!("{:.4}", number_column.get_median()),
+                    format_statistic(number_column.get_variance()),
+                    format_statistic(number_column.get_std()),
+                    number_column
+                        .get_unit()
+                        .map_or(String::new(), |unit| unit.get_name().to_owned()),
+                ]);
+                add_percentile_row(&mut percentile_table, &name, number_column);
+            }
+            ColumnSummary::Integer(integer_column) => {
+                let statistics = integer_column.get_statistics();
+                integer_table.rows.push(vec![
+                    name.clone(),
+                    nulls,
+                    null_percentage,
+                    statistics.get_count().to_string(),
+                    integer_column.get_sum().to_string(),
+                    integer_column.get_min().to_string(),
+                    integer_column.get_max().to_string(),
+                    integer_column.get_range().to_string(),
+                    statistics.get_min_line().to_string(),
+                    statistics.get_max_line().to_string(),
+                    format!("{:.4}", statistics.get_mean()),
+                    format!("{:.4}", statistics.get_median()),
+                    format_statistic(statistics.get_variance()),
+                    format_statistic(statistics.get_std()),
+                ]);
+                add_percentile_row(&mut percentile_table, &name, &statistics);
+            }
+            ColumnSummary::Boolean(boolean_column) => boolean_table.rows.push(vec![
+                name.clone(),
+                nulls,
+                null_percentage,
+                boolean_column.get_true_count().to_string(),
+                boolean_column.get_false_count().to_string(),
+                format!("{:.4}", boolean_column.get_true_ratio()),
+            ]),
+        }
+
+        top_value_table
+            .rows
+            .push(vec![name.clone(), format_top_values(&profile)]);
+        if profile.get_non_conforming_count() > 0 {
+            mixed_table.rows.push(vec![
+                name,
+                profile.get_non_conforming_count().to_string(),
+                format!("{:?}", profile.get_non_conforming_examples()),
+            ]);
+        }
+    }
+
+    return [
+        text_table,
+        pattern_table,
+        date_table,
+        datetime_table,
+        number_table,
+        integer_table,
+        percentile_table,
+        boolean_table,
+        top_value_table,
+        mixed_table,
+    ]
+    .into_iter()
+    .filter(|table| !table.rows.is_empty())
+    .collect();
+}
+
+/// adds the percentiles of a number or integer column to the percentile table. Every column has
+/// the same percentiles, the headers are taken from the first one
+fn add_percentile_row(
+    table: &mut Table,
+    column_name: &str,
+    number_column: &encapsulators::NumberColumn,
+) {
+    let percentiles = number_column.get_percentiles();
+    if table.rows.is_empty() {
+        table.headers.extend(
+            percentiles
+                .iter()
+                .map(|(percentile, _)| format_percentile(*percentile)),
+        );
+    }
+
+    let mut row = vec![
+        column_name.to_owned(),
+        format_rank_error(number_column.get_rank_error()),
+        format!("{:.4}", number_column.get_iqr()),
+    ];
+    row.extend(percentiles.iter().map(|(_, value)| format!("{:.4}", value)));
+    table.rows.push(row);
+}
@@ -0,0 +1,144 @@
+//! stores the settings shared by all workers that change how values are parsed and how column
+//! aggregates are turned into summaries.
+
+use crate::distinct;
+use crate::encapsulators::DateOrder;
+use crate::parsing::{self, EpochUnit, NumberLocale};
+use crate::quantiles;
+use std::collections::{HashMap, HashSet};
+
+/// settings set on the Summarizer and handed to every worker
+#[derive(Debug, Clone)]
+pub(crate) struct Settings {
+    // tokens treated as missing values
+    pub(crate) null_values: HashSet<String>,
+    // minimum share of non-null values of the dominant kind for a column to get that type
+    pub(crate) type_threshold: f64,
+    // unit of columns holding unix timestamps
+    pub(crate) epoch_units: ColumnOption<EpochUnit>,
+    // order of day and month in dates with the year last, None detects it from the values
+    pub(crate) date_orders: ColumnOption<Option<DateOrder>>,
+    // chrono formats tried before the date heuristic
+    pub(crate) date_formats: ColumnOption<Vec<String>>,
+    // decimal and grouping separators of numbers
+    pub(crate) number_locales: ColumnOption<NumberLocale>,
+    // definition of variance and standard deviation of number columns
+    pub(crate) variance_kind: VarianceKind,
+    // percentiles (0 to 100) reported for number columns
+    pub(crate) percentiles: Vec<f64>,
+    // size of the KLL sketch used for percentiles, None keeps exact counts
+    pub(crate) sketch_size: Option<usize>,
+    // number of most frequent values reported for every column
+    pub(crate) top_k: usize,
+    // number of distinct values of a column counted exactly before switching to an estimate
+    pub(crate) distinct_threshold: usize,
+}
+
+impl Settings {
+    /// returns a new Settings object with default null tokens, a type threshold of 0.9, sample
+    /// variance, exact default percentiles, the 5 most frequent values and exact distinct counts up
+    /// to the default threshold
+    pub(crate) fn new() -> Self {
+        return Settings {
+            null_values: parsing::DEFAULT_NULL_VALUES
+                .iter()
+                .map(|x| x.to_string())
+                .collect(),
+            type_threshold: 0.9,
+            epoch_units: ColumnOption::new(),
+            date_orders: ColumnOption::new(),
+            date_formats: ColumnOption::new(),
+            number_locales: ColumnOption::new(),
+            variance_kind: VarianceKind::Sample,
+            percentiles: quantiles::DEFAULT_PERCENTILES.to_vec(),
+            sketch_size: None,
+            top_k: 5,
+            distinct_threshold: distinct::DEFAULT_DISTINCT_THRESHOLD,
+        };
+    }
+
+    /// returns the options that apply to the column with the passed name
+    pub(crate) fn get_column_settings(&self, column_name: &str) -> ColumnSettings {
+        return ColumnSettings {
+            epoch_unit: self.epoch_units.get(column_name).copied(),
+            date_order: self.date_orders.get(column_name).copied().flatten(),
+            date_formats: self
+                .date_formats
+                .get(column_name)
+                .cloned()
+                .unwrap_or_default(),
+            number_locale: self
+                .number_locales
+                .get(column_name)
+                .copied()
+                .unwrap_or(parsing::DEFAULT_NUMBER_LOCALE),
+        };
+    }
+}
+
+/// definition used for variance and standard deviation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VarianceKind {
+    // divides by the number of values
+    Population,
+    // divides by the number of values minus one (Bessel's correction)
+    Sample,
+}
+
+/// option set either for all columns or for single columns by name
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnOption<T> {
+    all: Option<T>,
+    columns: HashMap<String, T>,
+}
+
+impl<T> ColumnOption<T> {
+    /// returns a new ColumnOption that is not set for any column
+    pub(crate) fn new() -> Self {
+        return ColumnOption {
+            all: None,
+            columns: HashMap::new(),
+        };
+    }
+
+    /// sets the value for the named column, or for every column without its own value when
+    /// column_name is None
+    pub(crate) fn set(&mut self, column_name: Option<String>, value: T) {
+        match column_name {
+            Some(column_name) => {
+                self.columns.insert(column_name, value);
+            }
+            None => self.all = Some(value),
+        }
+    }
+
+    /// returns the value set for the named column, falling back to the value for every column
+    pub(crate) fn get(&self, column_name: &str) -> Option<&T> {
+        return self.columns.get(column_name).or(self.all.as_ref());
+    }
+
+    /// returns the value for the named column, or for every column when column_name is None,
+    /// setting it to the default value first if it is not set
+    pub(crate) fn get_or_default(&mut self, column_name: Option<String>) -> &mut T
+    where
+        T: Default,
+    {
+        return match column_name {
+            Some(column_name) => self.columns.entry(column_name).or_default(),
+            None => self.all.get_or_insert_with(T::default),
+        };
+    }
+}
+
+/// options of a single column, resolved from Settings once the column names are known
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnSettings {
+    // integer and float values are unix timestamps in this unit
+    pub(crate) epoch_unit: Option<EpochUnit>,
+    // order of day and month in dates with the year last, None detects it from the values
+    pub(crate) date_order: Option<DateOrder>,
+    // chrono formats tried before the date heuristic, values matching them are dates
+    pub(crate) date_formats: Vec<String>,
+    // decimal and grouping separators of numbers in the column
+    pub(crate) number_locale: NumberLocale,
+}
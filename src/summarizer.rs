@@ -0,0 +1,504 @@
+//! drives the summary of a whole csv source: reads it in batches, hands the batches to a fixed pool
+//! of worker threads and merges the partial aggregates they return.
+
+use crate::aggregator;
+use crate::chunker;
+use crate::dialect::Dialect;
+use crate::encapsulators::{self, DateOrder};
+use crate::error::SummaryError;
+use crate::parsing::{self, EpochUnit, NumberLocale};
+use crate::settings::{ColumnSettings, Settings, VarianceKind};
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+/// size of a chunk of raw bytes handed to a worker at once
+const CHUNK_SIZE: usize = 4 << 20;
+
+/// number of bytes from the start of the source inspected when sniffing the delimiter
+const SNIFF_SIZE: usize = 16 << 10;
+
+/// number of already parsed records handed to a worker at once
+const RECORD_BATCH_SIZE: usize = 1024;
+
+/// number of batches per worker that can wait in the channel before the reader blocks. Keeps memory
+/// flat when workers fall behind the reader
+const QUEUED_BATCHES: usize = 2;
+
+/// piece of the csv source passed to a worker
+enum Batch {
+    // raw bytes ending on a record boundary, parsed by the worker
+    Chunk(chunker::Chunk),
+    // records already parsed by a csv::Reader
+    Records(Vec<csv::StringRecord>),
+}
+
+/// handle of a worker, which gives back partial aggregates of all columns for the batches it
+/// handled, or the error that stopped it
+type WorkerHandle = thread::JoinHandle<Result<Vec<aggregator::ColumnAggregator>, SummaryError>>;
+
+/// result of a summary: column names with their summaries, in header order
+type SummaryResult = Result<Vec<(String, encapsulators::ColumnSummary)>, SummaryError>;
+
+/// summarizes csv sources column by column. Returns one ColumnSummary per column, in the same order
+/// as the headers of the source
+pub struct Summarizer {
+    threads: usize,
+    dialect: Dialect,
+    sniff_delimiter: bool,
+    has_headers: bool,
+    column_names: Option<Vec<String>>,
+    settings: Settings,
+}
+
+impl Summarizer {
+    /// returns a new Summarizer that uses one worker thread per cpu
+    pub fn new() -> Self {
+        return Summarizer {
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            dialect: Dialect::new(),
+            sniff_delimiter: false,
+            has_headers: true,
+            column_names: None,
+            settings: Settings::new(),
+        };
+    }
+
+    /// sets the number of worker threads, at least one worker is always used
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// returns the number of worker threads
+    pub fn get_threads(&self) -> usize {
+        return self.threads;
+    }
+
+    /// sets the dialect used to parse sources passed to summarize_reader
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect;
+    }
+
+    /// returns the dialect used to parse sources passed to summarize_reader
+    pub fn get_dialect(&self) -> &Dialect {
+        return &self.dialect;
+    }
+
+    /// sets whether summarize_reader picks the delimiter by inspecting the start of the source
+    /// instead of using the delimiter of the dialect
+    pub fn set_sniff_delimiter(&mut self, sniff_delimiter: bool) {
+        self.sniff_delimiter = sniff_delimiter;
+    }
+
+    /// sets whether the first record of the source is a header. Sources without a header use the
+    /// column names set with set_column_names, or column_1..N
+    pub fn set_has_headers(&mut self, has_headers: bool) {
+        self.has_headers = has_headers;
+    }
+
+    /// sets names of the columns for sources without a header, None generates column_1..N. The
+    /// number of names has to match the number of fields of the first record
+    pub fn set_column_names(&mut self, column_names: Option<Vec<String>>) {
+        self.column_names = column_names;
+    }
+
+    /// sets the tokens treated as missing values. Nulls are counted for every column, but do not
+    /// take part in type inference and statistics
+    pub fn set_null_values(&mut self, null_values: Vec<String>) {
+        self.settings.null_values = null_values.into_iter().collect();
+    }
+
+    /// sets the minimum share (0.0 to 1.0) of non-null values that must parse as numbers or dates
+    /// for the column to get that type. Columns without such a dominant kind are text columns
+    pub fn set_type_threshold(&mut self, type_threshold: f64) {
+        self.settings.type_threshold = type_threshold.clamp(0.0, 1.0);
+    }
+
+    /// sets the unit of columns holding unix timestamps, which are summarized as timestamps instead
+    /// of numbers. None as column_name applies the unit to every column without its own unit
+    pub fn set_epoch_unit(&mut self, column_name: Option<String>, epoch_unit: EpochUnit) {
+        self.settings.epoch_units.set(column_name, epoch_unit);
+    }
+
+    /// sets the order of day and month in dates with the year last. None detects the order from
+    /// the values of the column, None as column_name applies the order to every column without its
+    /// own order
+    pub fn set_date_order(&mut self, column_name: Option<String>, date_order: Option<DateOrder>) {
+        self.settings.date_orders.set(column_name, date_order);
+    }
+
+    /// adds a chrono format, like `%Y%m%d`, tried on values before the date heuristic and number
+    /// parsing. None as column_name adds the format for every column without its own formats
+    pub fn add_date_format(&mut self, column_name: Option<String>, date_format: String) {
+        self.settings
+            .date_formats
+            .get_or_default(column_name)
+            .push(date_format);
+    }
+
+    /// sets the decimal and grouping separators of numbers in the named column, or in every column
+    /// without its own locale when column_name is None. Numbers default to 1,234.56
+    pub fn set_number_locale(&mut self, column_name: Option<String>, number_locale: NumberLocale) {
+        self.settings.number_locales.set(column_name, number_locale);
+    }
+
+    /// sets whether variance and standard deviation of number columns use the population or the
+    /// sample (Bessel-corrected) definition
+    pub fn set_variance_kind(&mut self, variance_kind: VarianceKind) {
+        self.settings.variance_kind = variance_kind;
+    }
+
+    /// sets the percentiles (0 to 100) reported for number columns, next to the median and the
+    /// interquartile range which are always reported
+    pub fn set_percentiles(&mut self, percentiles: Vec<f64>) {
+        self.settings.percentiles = percentiles
+            .into_iter()
+            .map(|percentile| percentile.clamp(0.0, 100.0))
+            .collect();
+    }
+
+    /// sets the size of the KLL sketch used for the median and percentiles of number columns, which
+    /// bounds memory but makes them approximate. Larger sketches are more accurate. None counts
+    /// every distinct value for exact percentiles
+    pub fn set_sketch_size(&mut self, sketch_size: Option<usize>) {
+        self.settings.sketch_size = sketch_size.map(|k| k.max(8));
+    }
+
+    /// sets the number of most frequent values reported for every column
+    pub fn set_top_k(&mut self, top_k: usize) {
+        self.settings.top_k = top_k;
+    }
+
+    /// sets the number of distinct values of a column that are counted exactly. Columns with more
+    /// distinct values get a HyperLogLog estimate of their distinct count, and their top values
+    /// come from the most frequent values kept, with counts that are lower bounds
+    pub fn set_distinct_threshold(&mut self, distinct_threshold: usize) {
+        self.settings.distinct_threshold = distinct_threshold.max(1);
+    }
+
+    /// summarizes csv data read from any reader, parsed with the dialect of the Summarizer. The
+    /// reader is split into chunks of raw bytes that are parsed in parallel by the workers
+    pub fn summarize_reader<R: Read>(&self, reader: R) -> SummaryResult {
+        let mut dialect = self.dialect.clone();
+        let mut chunk_reader = chunker::ChunkReader::new(reader, &dialect);
+
+        if self.sniff_delimiter {
+            dialect.sniff_delimiter(chunk_reader.peek(SNIFF_SIZE)?);
+            chunk_reader.set_dialect(&dialect);
+        }
+
+        // the header is the first record of the source, skipping comments and empty lines. Without
+        // a header the first record is only used to count the columns and is summarized as data
+        let mut first_record: Vec<String> = vec![];
+        let mut first_chunk: Option<chunker::Chunk> = None;
+        while let Some(chunk) = chunk_reader.next_chunk(1)? {
+            let mut header_reader = dialect.reader_builder().from_reader(chunk.bytes.as_slice());
+
+            // makes a vec of owned strings
+            if let Some(header) = header_reader.records().next() {
+                first_record = header?.iter().map(|x| x.to_owned()).collect();
+                if !self.has_headers {
+                    first_chunk = Some(chunk);
+                }
+                break;
+            }
+        }
+        let headers = self.get_headers(first_record)?;
+
+        let (handles, sender, failed) = self.get_worker_pool(&headers, &dialect);
+        if let Some(chunk) = first_chunk {
+            let _ = sender.send(Batch::Chunk(chunk));
+        }
+
+        // read the source in chunks that end on a record boundary, and send them to the workers.
+        // Stop early if a worker already failed, its error is returned by merge_workers
+        let mut read_result: Result<(), SummaryError> = Ok(());
+        while !failed.load(Ordering::Relaxed) {
+            match chunk_reader.next_chunk(CHUNK_SIZE) {
+                Ok(Some(chunk)) => {
+                    if sender.send(Batch::Chunk(chunk)).is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    read_result = Err(SummaryError::Io(e));
+                    break;
+                }
+            }
+        }
+
+        // dropping the sender closes the channel, so that the workers stop working
+        drop(sender);
+
+        let column_summaries = merge_workers(headers, handles, &self.settings);
+        read_result?;
+        return column_summaries;
+    }
+
+    /// summarizes the records of an existing csv::Reader, keeping its configuration. The reader is
+    /// read on the calling thread, the workers only aggregate the parsed records
+    pub fn summarize_csv_reader<R: Read>(&self, mut reader: csv::Reader<R>) -> SummaryResult {
+        // makes a vec of owned strings. Without a header the reader returns the first record here
+        // without consuming it
+        let first_record: Vec<String> = reader.headers()?.iter().map(|x| x.to_owned()).collect();
+        let headers = if reader.has_headers() {
+            first_record
+        } else {
+            self.get_headers_for_headerless(first_record.len())?
+        };
+
+        let (handles, sender, failed) = self.get_worker_pool(&headers, &self.dialect);
+
+        // read the csv line by line, and send the rows to the workers in batches. Stop early if a
+        // worker already failed, its error is returned by merge_workers
+        let mut read_result: Result<(), SummaryError> = Ok(());
+        let mut batch: Vec<csv::StringRecord> = Vec::with_capacity(RECORD_BATCH_SIZE);
+        for line in reader.into_records() {
+            if failed.load(Ordering::Relaxed) {
+                break;
+            }
+            match line {
+                Ok(record) => batch.push(record),
+                Err(e) => {
+                    read_result = Err(SummaryError::from(e));
+                    break;
+                }
+            }
+
+            if batch.len() == RECORD_BATCH_SIZE {
+                let full_batch =
+                    std::mem::replace(&mut batch, Vec::with_capacity(RECORD_BATCH_SIZE));
+                if sender.send(Batch::Records(full_batch)).is_err() {
+                    break;
+                }
+            }
+        }
+        if !batch.is_empty() && read_result.is_ok() {
+            let _ = sender.send(Batch::Records(batch));
+        }
+
+        // dropping the sender closes the channel, so that the workers stop working
+        drop(sender);
+
+        let column_summaries = merge_workers(headers, handles, &self.settings);
+        read_result?;
+        return column_summaries;
+    }
+
+    /// returns the column names for a source whose first record was passed, which is the header
+    /// itself unless the Summarizer is set up for sources without a header
+    fn get_headers(&self, first_record: Vec<String>) -> Result<Vec<String>, SummaryError> {
+        if self.has_headers {
+            return Ok(first_record);
+        }
+        return self.get_headers_for_headerless(first_record.len());
+    }
+
+    /// returns the column names set with set_column_names, or column_1..N for N columns. Returns a
+    /// ColumnNameCount error when the names set do not match the N fields of the first record
+    fn get_headers_for_headerless(&self, column_count: usize) -> Result<Vec<String>, SummaryError> {
+        return match &self.column_names {
+            // an empty source has no record to check the names against
+            Some(column_names) if column_count > 0 && column_names.len() != column_count => {
+                Err(SummaryError::ColumnNameCount {
+                    expected: column_count as u64,
+                    found: column_names.len() as u64,
+                })
+            }
+            Some(column_names) => Ok(column_names.clone()),
+            None => Ok((1..=column_count)
+                .map(|index| format!("column_{}", index))
+                .collect()),
+        };
+    }
+
+    /// creates a fixed number of worker threads that take batches from a shared bounded channel.
+    /// Each worker builds partial aggregates for every header from the batches it got, which are
+    /// merged once the source is read. Returns handles for all workers, the sender used to pass the
+    /// batches, and a flag raised when a worker stops on an error. Dropping the sender tells the
+    /// workers that the source has ended
+    fn get_worker_pool(
+        &self,
+        headers: &[String],
+        dialect: &Dialect,
+    ) -> (Vec<WorkerHandle>, mpsc::SyncSender<Batch>, Arc<AtomicBool>) {
+        // get bounded sender and receiver shared by all workers
+        let (tx, rx) = mpsc::sync_channel::<Batch>(self.threads * QUEUED_BATCHES);
+        let rx = Arc::new(Mutex::new(rx));
+        let failed = Arc::new(AtomicBool::new(false));
+        let settings = Arc::new(self.settings.clone());
+        let column_settings: Arc<Vec<ColumnSettings>> = Arc::new(
+            headers
+                .iter()
+                .map(|header| self.settings.get_column_settings(header))
+                .collect(),
+        );
+
+        let mut handles: Vec<WorkerHandle> = vec![];
+        for _ in 0..self.threads {
+            let rx = Arc::clone(&rx);
+            let failed = Arc::clone(&failed);
+            let dialect = dialect.clone();
+            let settings = Arc::clone(&settings);
+            let column_settings = Arc::clone(&column_settings);
+
+            let handle = thread::spawn(move || {
+                let mut aggregators: Vec<aggregator::ColumnAggregator> = column_settings
+                    .iter()
+                    .map(|_| aggregator::ColumnAggregator::new(&settings))
+                    .collect();
+
+                // wait for batches from the reader and handle them when they arrive. The loop ends
+                // when the reader drops the sender or any worker fails
+                while !failed.load(Ordering::Relaxed) {
+                    let batch = match rx.lock() {
+                        Ok(receiver) => match receiver.recv() {
+                            Ok(batch) => batch,
+                            Err(_) => break,
+                        },
+                        Err(_) => return Err(SummaryError::ThreadJoin),
+                    };
+
+                    let aggregated = aggregate_batch(
+                        &mut aggregators,
+                        batch,
+                        &dialect,
+                        &settings,
+                        &column_settings,
+                    );
+                    if let Err(e) = aggregated {
+                        failed.store(true, Ordering::Relaxed);
+                        return Err(e);
+                    }
+                }
+
+                return Ok(aggregators);
+            });
+
+            handles.push(handle);
+        }
+
+        return (handles, tx, failed);
+    }
+}
+
+/// parses the batch with the dialect if needed and adds every record to the aggregates
+fn aggregate_batch(
+    aggregators: &mut [aggregator::ColumnAggregator],
+    batch: Batch,
+    dialect: &Dialect,
+    settings: &Settings,
+    column_settings: &[ColumnSettings],
+) -> Result<(), SummaryError> {
+    match batch {
+        Batch::Chunk(chunk) => {
+            // record lengths are checked against the header in aggregate_record
+            let mut chunk_reader = dialect.reader_builder().from_reader(chunk.bytes.as_slice());
+
+            for line in chunk_reader.records() {
+                // positions reported by the reader are relative to the chunk
+                let record = line.map_err(|e| match SummaryError::from(e) {
+                    SummaryError::Csv {
+                        line: Some(line),
+                        source,
+                    } => SummaryError::Csv {
+                        line: Some(chunk.first_line + line - 1),
+                        source,
+                    },
+                    other => other,
+                })?;
+                let line = record
+                    .position()
+                    .map_or(chunk.first_line, |p| chunk.first_line + p.line() - 1);
+                aggregate_record(aggregators, &record, line, settings, column_settings)?;
+            }
+        }
+        Batch::Records(records) => {
+            for record in records.iter() {
+                let line = record.position().map_or(0, |p| p.line());
+                aggregate_record(aggregators, record, line, settings, column_settings)?;
+            }
+        }
+    }
+
+    return Ok(());
+}
+
+/// adds every value of one record to the aggregate of its column. Returns a RaggedRow error if the
+/// record does not have one value per column
+fn aggregate_record(
+    aggregators: &mut [aggregator::ColumnAggregator],
+    record: &csv::StringRecord,
+    line: u64,
+    settings: &Settings,
+    column_settings: &[ColumnSettings],
+) -> Result<(), SummaryError> {
+    if record.len() != aggregators.len() {
+        return Err(SummaryError::RaggedRow {
+            line,
+            expected: aggregators.len() as u64,
+            found: record.len() as u64,
+        });
+    }
+
+    let columns = aggregators.iter_mut().zip(column_settings);
+    for (value, (column_aggregator, column_settings)) in record.iter().zip(columns) {
+        column_aggregator.add(
+            value,
+            parsing::convert_value(value, settings, column_settings),
+            line,
+        );
+    }
+
+    return Ok(());
+}
+
+/// joins all workers and merges their partial aggregates column by column, then builds the final
+/// summaries in header order. Returns the first error reported by a worker
+fn merge_workers(
+    headers: Vec<String>,
+    handles: Vec<WorkerHandle>,
+    settings: &Settings,
+) -> SummaryResult {
+    let mut aggregators: Vec<aggregator::ColumnAggregator> = headers
+        .iter()
+        .map(|_| aggregator::ColumnAggregator::new(settings))
+        .collect();
+
+    // join every worker before returning, so that no thread outlives the summary
+    let mut worker_error: Option<SummaryError> = None;
+    for handle in handles {
+        let worker_result = match handle.join() {
+            Ok(worker_result) => worker_result,
+            Err(_) => Err(SummaryError::ThreadJoin),
+        };
+
+        match worker_result {
+            Ok(worker_aggregators) => {
+                for (column_aggregator, worker_aggregator) in
+                    aggregators.iter_mut().zip(worker_aggregators)
+                {
+                    column_aggregator.merge(worker_aggregator);
+                }
+            }
+            Err(e) => {
+                if worker_error.is_none() {
+                    worker_error = Some(e);
+                }
+            }
+        }
+    }
+    if let Some(e) = worker_error {
+        return Err(e);
+    }
+
+    let mut column_summaries: Vec<(String, encapsulators::ColumnSummary)> = vec![];
+    for (header, column_aggregator) in headers.into_iter().zip(aggregators) {
+        let column_summary = column_aggregator.build_summary(&header, settings)?;
+        column_summaries.push((header, column_summary));
+    }
+
+    return Ok(column_summaries);
+}